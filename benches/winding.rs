@@ -0,0 +1,86 @@
+//! Times computing the word of long paths around many puncture points from scratch,
+//! against testing every segment against the puncture points one segment at a time.
+//!
+//! Run with `cargo bench --bench winding`.
+
+use charred_path::{
+    piecewise_linear::{PLPath, PathType, PuncturePoint},
+    word::Label,
+};
+use glam::Vec2;
+use std::{
+    fmt,
+    hint::black_box,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// How many times each measurement is repeated; the median is reported.
+const RUNS: usize = 15;
+
+/// Numbered puncture points, as there are more of them than single-letter names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Hole(usize);
+
+impl fmt::Display for Hole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "h{}", self.0)
+    }
+}
+
+impl Label for Hole {}
+
+/// Deterministic scattered values in `[0, 1)`.
+fn noise(i: usize) -> f32 {
+    ((i as f32 * 12.9898).sin() * 43_758.547).rem_euclid(1.0)
+}
+
+/// The median time taken by `f` over [`RUNS`] runs.
+fn median_time<T>(mut f: impl FnMut() -> T) -> Duration {
+    let mut times: Vec<Duration> = (0..RUNS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    times.sort();
+    times[RUNS / 2]
+}
+
+fn main() {
+    println!(
+        "{:>7} {:>10} {:>12} {:>12}",
+        "nodes", "punctures", "blocked", "per segment"
+    );
+    for (node_count, puncture_count) in [(1_000, 16), (10_000, 64), (50_000, 256)] {
+        let puncture_points: Arc<[PuncturePoint<Hole>]> = (0..puncture_count)
+            .map(|i| {
+                PuncturePoint::labelled(Vec2::new(noise(2 * i), noise(2 * i + 1)) * 100.0, Hole(i))
+            })
+            .collect();
+        // A random walk with unit steps, like a recorded player path, folded back into the field.
+        let nodes: Vec<Vec2> = (0..node_count)
+            .scan(Vec2::splat(50.0), |position, i| {
+                let step = Vec2::from_angle(noise(i + 1000) * std::f32::consts::TAU);
+                *position = (*position + step).rem_euclid(Vec2::splat(100.0));
+                Some(*position)
+            })
+            .collect();
+        let path = PLPath::new(nodes.clone());
+
+        let path_type = PathType::from_path(path.clone(), puncture_points.clone());
+        let per_segment_letters = || -> Vec<Vec<(Hole, i32)>> {
+            nodes
+                .iter()
+                .zip(nodes.iter().cycle().skip(1))
+                .map(|(start, end)| path_type.segment_winding(*start, *end))
+                .collect()
+        };
+        assert_eq!(path_type.segment_generators(), per_segment_letters());
+
+        let blocked = median_time(|| PathType::from_path(path.clone(), puncture_points.clone()));
+        let per_segment = median_time(per_segment_letters);
+        println!("{node_count:>7} {puncture_count:>10} {blocked:>12.2?} {per_segment:>12.2?}");
+    }
+}
@@ -7,7 +7,7 @@ const PLAYER_START: Vec3 = Vec3::new(0.0, 0.0, 0.0);
 
 fn main() {
     let mut app = App::new();
-    app.add_plugins((DefaultPlugins, PathPlugin, PathDebugPlugin));
+    app.add_plugins((DefaultPlugins, PathPlugin, PathDebugPlugin::default()));
     app.add_systems(Startup, init);
     app.add_systems(FixedUpdate, player_movement);
     app.add_systems(Update, homotopy_text_update);
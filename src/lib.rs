@@ -1,5 +1,7 @@
 pub mod piecewise_linear;
+pub mod word;
 
 pub mod prelude {
     pub use crate::piecewise_linear::*;
+    pub use crate::word::*;
 }
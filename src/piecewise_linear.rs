@@ -1,63 +1,55 @@
-use bevy::prelude::*;
-use std::{cmp::Ordering, sync::Arc, time::Duration};
+use crate::word::{letter_with_case, letters_are_reduced, reduce_letters, render_letters, Label};
+#[cfg(all(feature = "bevy", feature = "serialize"))]
+use bevy::{
+    ecs::reflect::ReflectComponent,
+    reflect::{ReflectDeserialize, ReflectSerialize},
+};
+#[cfg(feature = "bevy")]
+use bevy::{
+    math::primitives::Segment2d,
+    prelude::{Component, Rect, Transform},
+    tasks::{AsyncComputeTaskPool, Task, TaskPool},
+};
+use glam::{IVec2, Vec2};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet, VecDeque},
+    ops::{Mul, Neg},
+    sync::{Arc, PoisonError, RwLock},
+    time::Duration,
+};
 
-/// Adds systems for updating the path timer and updating the position of entities along the path.
-pub struct PathPlugin;
-
-impl Plugin for PathPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Update, (tick_path_timer, update_entity_position))
-            .insert_resource(PathTimer::default());
-    }
-}
-
-/// Plugin for debugging paths.
-/// Adds a system for rendering paths to the screen using Bevy's 2d primitives.
-pub struct PathDebugPlugin;
-
-impl Plugin for PathDebugPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Update, debug_render_paths);
-    }
-}
+#[cfg(feature = "bevy")]
+mod plugin;
+#[cfg(feature = "bevy")]
+pub use plugin::*;
 
 /// Checks if the prior node should be removed. Returns true if it should be removed.
-fn should_remove(p1: &Vec2, p2: &Vec2, p3: &Vec2, puncture_points: &[PuncturePoint]) -> bool {
+fn should_remove<L>(p1: &Vec2, p2: &Vec2, p3: &Vec2, puncture_points: &[PuncturePoint<L>]) -> bool {
     puncture_points.iter().all(|p| p.should_remove(p1, p2, p3))
 }
 
-/// Resource struct representing a timer for path updates.
-#[derive(Resource)]
-pub struct PathTimer {
-    pub timer: Timer,
-}
+/// A small SplitMix64 pseudo-random generator, used for reproducible seeded layouts.
+struct SplitMix64(u64);
 
-impl Default for PathTimer {
-    fn default() -> Self {
-        Self {
-            timer: Timer::new(Duration::from_millis(250), TimerMode::Repeating),
-        }
+impl SplitMix64 {
+    const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
     }
-}
 
-/// Updates the path timer.
-fn tick_path_timer(mut path_timer: ResMut<PathTimer>, time: Res<Time>) {
-    path_timer.timer.tick(time.delta());
-}
+    /// A float uniformly distributed in `[0, 1)`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
 
-/// Updates the position of entities along the path.
-fn update_entity_position(
-    mut path_query: Query<(&mut PathType, &Transform)>,
-    // path_timer: Res<PathTimer>,
-) {
-    // if path_timer.timer.just_finished() {
-    for (mut path_type, transform) in path_query.iter_mut() {
-        let current_position = transform.translation.truncate();
-        if &current_position != path_type.current_path.end() {
-            path_type.push(&current_position);
-        }
+    /// An integer uniformly distributed in `0..n`.
+    const fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
     }
-    // }
 }
 
 /// `PuncturePoint` represents a hole in the plane from the perspective of homotopy.
@@ -67,16 +59,28 @@ fn update_entity_position(
 ///
 /// Each `PuncturePoint` contains a `position` which is a `Vec2` representing the position of the point,
 /// and a `name` which is a `char` that uniquely identifies the puncture point. It is used to represent
-/// the traversal around the puncture point when writing the homotopy type of a path. `name.to_ascii_lowercase()`
-/// is used to represent clockwise traversal around the puncture point, and `name.to_ascii_uppercase()` is used
-/// to represent counterclockwise traversal.
+/// the traversal around the puncture point when writing the homotopy type of a path. `name` in lower case
+/// is used to represent clockwise traversal around the puncture point, and `name` in upper case is used
+/// to represent counterclockwise traversal. Case is changed over all of Unicode, like [`crate::word::invert_word`].
 ///
 /// Note that the name character is made uppercase upon instantiation.
 ///
+/// A puncture point may also be a cone point with a finite `order` `n`, in which case winding
+/// `n` times around it is trivial and the relation `x^n = 1` is applied when reducing words.
+///
+/// A puncture point may also carry a [`PunctureShape`], the footprint of the obstacle it stands for.
+/// Paths are never tightened through the footprint, but the winding is still measured around `position`.
+///
+/// Puncture points are ordered by name, not by position, so sorting a list of them
+/// puts them in alphabetical order. Position, then order and shape, only break ties between equally named points.
+///
+/// Names other than `char` can be used through the label type `L`, see [`Label`] and
+/// [`PuncturePoint::labelled`]. `PuncturePoint` on its own means `PuncturePoint<char>`.
+///
 /// # Examples
 ///
 /// ```
-/// use bevy::prelude::*;
+/// use glam::Vec2;
 /// use charred_path::piecewise_linear::PuncturePoint;
 ///
 /// let position = Vec2::new(1.0, 2.0);
@@ -84,19 +88,324 @@ fn update_entity_position(
 /// assert_eq!(puncture_point.position(), &position);
 /// assert_eq!(puncture_point.name(), 'A');
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Component)]
-pub struct PuncturePoint {
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PuncturePoint<L = char> {
     position: Vec2,
-    name: char,
+    name: L,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    order: Option<u32>,
+    #[cfg_attr(feature = "serialize", serde(default))]
+    shape: PunctureShape,
+}
+
+/// The footprint of the obstacle represented by a [`PuncturePoint`], centered on its position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum PunctureShape {
+    /// A disk of the given radius. A radius of `0.0` is a bare point.
+    Circle { radius: f32 },
+    /// An axis-aligned rectangle with the given half-extents.
+    Rect { half_size: Vec2 },
+    /// An axis-aligned ellipse with the given semi-axes.
+    Ellipse { half_axes: Vec2 },
+}
+
+impl Default for PunctureShape {
+    fn default() -> Self {
+        Self::Circle { radius: 0.0 }
+    }
+}
+
+impl PunctureShape {
+    /// A total order on shapes: by variant, then by their sizes compared with [`f32::total_cmp`].
+    fn total_cmp(&self, other: &Self) -> Ordering {
+        let key = |shape: &Self| match *shape {
+            Self::Circle { radius } => (0, [radius, 0.0]),
+            Self::Rect { half_size } => (1, half_size.to_array()),
+            Self::Ellipse { half_axes } => (2, half_axes.to_array()),
+        };
+        let ((variant, size), (other_variant, other_size)) = (key(self), key(other));
+        variant
+            .cmp(&other_variant)
+            .then_with(|| size[0].total_cmp(&other_size[0]))
+            .then_with(|| size[1].total_cmp(&other_size[1]))
+    }
+
+    /// Checks if the segment from `start` to `end` passes through the interior of the shape centered at `center`.
+    fn overlaps_segment(&self, center: Vec2, start: Vec2, end: Vec2) -> bool {
+        match *self {
+            Self::Circle { radius } => segment_distance(center, start, end) < radius,
+            Self::Ellipse { half_axes } => {
+                half_axes.min_element() > 0.0
+                    && segment_distance(
+                        Vec2::ZERO,
+                        (start - center) / half_axes,
+                        (end - center) / half_axes,
+                    ) < 1.0
+            }
+            Self::Rect { half_size } => {
+                let direction = end - start;
+                let (mut enter, mut exit) = (0.0_f32, 1.0_f32);
+                for axis in 0..2 {
+                    let (low, high) = (
+                        center[axis] - half_size[axis],
+                        center[axis] + half_size[axis],
+                    );
+                    if direction[axis] == 0.0 {
+                        if start[axis] <= low || start[axis] >= high {
+                            return false;
+                        }
+                    } else {
+                        let t_low = (low - start[axis]) / direction[axis];
+                        let t_high = (high - start[axis]) / direction[axis];
+                        enter = enter.max(t_low.min(t_high));
+                        exit = exit.min(t_low.max(t_high));
+                    }
+                }
+                enter < exit
+            }
+        }
+    }
+
+    /// If `point` is strictly inside the shape centered at `center`, returns where the ray from `point`
+    /// along the unit vector `direction` leaves the shape.
+    fn exit_along(&self, center: Vec2, point: Vec2, direction: Vec2) -> Option<Vec2> {
+        let offset = point - center;
+        let t = match *self {
+            Self::Circle { radius } => {
+                let distance_squared = offset.length_squared();
+                if distance_squared >= radius * radius {
+                    return None;
+                }
+                let b = offset.dot(direction);
+                b.mul_add(b, radius.mul_add(radius, -distance_squared))
+                    .sqrt()
+                    - b
+            }
+            Self::Ellipse { half_axes } => {
+                if half_axes.min_element() <= 0.0 {
+                    return None;
+                }
+                let (offset, direction) = (offset / half_axes, direction / half_axes);
+                let c = offset.length_squared() - 1.0;
+                if c >= 0.0 {
+                    return None;
+                }
+                let (a, b) = (direction.length_squared(), offset.dot(direction));
+                (b.mul_add(b, -a * c).sqrt() - b) / a
+            }
+            Self::Rect { half_size } => {
+                if offset.abs().cmpge(half_size).any() {
+                    return None;
+                }
+                (0..2)
+                    .filter(|&axis| direction[axis] != 0.0)
+                    .map(|axis| {
+                        (half_size[axis].copysign(direction[axis]) - offset[axis]) / direction[axis]
+                    })
+                    .fold(f32::INFINITY, f32::min)
+            }
+        };
+        Some(point + direction * t)
+    }
+}
+
+/// How far past the edge of a puncture point's shape [`PathType::with_puncture_avoidance`] moves a pushed point.
+const PUNCTURE_AVOIDANCE_MARGIN: f32 = 1e-3;
+
+#[cfg(test)]
+thread_local! {
+    /// How many times [`PathType::update_word`] has recomputed a word on this thread, so tests can see skipped work.
+    static WORD_RECOMPUTATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Positions and shapes are compared with [`f32::total_cmp`], agreeing with [`Ord`]:
+/// a position is equal to itself even when it is NaN, and `0.0` and `-0.0` differ.
+impl<L: PartialEq> PartialEq for PuncturePoint<L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+            && self.position.x.total_cmp(&other.position.x).is_eq()
+            && self.position.y.total_cmp(&other.position.y).is_eq()
+            && self.order == other.order
+            && self.shape.total_cmp(&other.shape).is_eq()
+    }
+}
+
+impl<L: Eq> Eq for PuncturePoint<L> {}
+
+impl<L: Ord> PartialOrd for PuncturePoint<L> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<L: Ord> Ord for PuncturePoint<L> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.name
+            .cmp(&other.name)
+            .then_with(|| self.position.x.total_cmp(&other.position.x))
+            .then_with(|| self.position.y.total_cmp(&other.position.y))
+            .then_with(|| self.order.cmp(&other.order))
+            .then_with(|| self.shape.total_cmp(&other.shape))
+    }
 }
 
 impl PuncturePoint {
     /// Represents a puncture point in the plane.
-    pub const fn new(position: Vec2, name: char) -> Self {
+    pub fn new(position: Vec2, name: char) -> Self {
+        Self::labelled(position, letter_with_case(name, true))
+    }
+
+    /// Puncture points at the given positions, named `A`, `B`, `C`, ... in order.
+    ///
+    /// ## Panics
+    /// This will panic if there are more than 26 positions, since names are single letters.
+    pub fn auto_named(positions: impl IntoIterator<Item = Vec2>) -> Vec<Self> {
+        positions
+            .into_iter()
+            .zip(0u8..)
+            .map(|(position, index)| {
+                assert!(index < 26, "Ran out of single-letter puncture names");
+                Self::new(position, char::from(b'A' + index))
+            })
+            .collect()
+    }
+
+    /// Lays out `rows * cols` puncture points on a grid, row by row, starting at `origin`.
+    ///
+    /// Puncture points are named `A`, `B`, `C`, ... in layout order.
+    ///
+    /// ## Panics
+    /// This will panic if the grid has more than 26 puncture points.
+    pub fn grid(rows: usize, cols: usize, spacing: Vec2, origin: Vec2) -> Vec<Self> {
+        Self::auto_named(
+            (0..rows)
+                .flat_map(|row| (0..cols).map(move |col| Vec2::new(col as f32, row as f32)))
+                .map(|offset| origin + offset * spacing),
+        )
+    }
+
+    /// Scatters `count` puncture points at pseudo-random positions inside `rect`, see [`PuncturePoint::random_in_box`].
+    ///
+    /// ## Panics
+    /// This will panic if `count` is more than 26.
+    #[cfg(feature = "bevy")]
+    pub fn random_in_rect(rect: Rect, count: usize, seed: u64) -> Vec<Self> {
+        Self::random_in_box(rect.min, rect.max, count, seed)
+    }
+
+    /// Scatters `count` puncture points at pseudo-random positions inside the axis-aligned box from `min` to `max`.
+    ///
+    /// The same `seed` always produces the same layout. The box is divided into a
+    /// `ceil(sqrt(count))`-wide grid of cells, each puncture point lands in the inner half of
+    /// a distinct cell, so any two puncture points are at least half of the smaller cell side apart.
+    /// Puncture points are named `A`, `B`, `C`, ... in generation order.
+    ///
+    /// ## Panics
+    /// This will panic if `count` is more than 26.
+    pub fn random_in_box(min: Vec2, max: Vec2, count: usize, seed: u64) -> Vec<Self> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let cols = (count as f32).sqrt().ceil() as usize;
+        let rows = count.div_ceil(cols);
+        let cell_size = (max - min) / Vec2::new(cols as f32, rows as f32);
+        let mut rng = SplitMix64(seed);
+        let mut cells: Vec<usize> = (0..rows * cols).collect();
+        let positions: Vec<Vec2> = (0..count)
+            .map(|index| {
+                let pick = index + rng.below(cells.len() - index);
+                cells.swap(index, pick);
+                let (row, col) = (cells[index] / cols, cells[index] % cols);
+                let jitter = Vec2::new(rng.next_f32(), rng.next_f32())
+                    .mul_add(Vec2::splat(0.5), Vec2::splat(0.25));
+                let cell_min = min + Vec2::new(col as f32, row as f32) * cell_size;
+                cell_min + jitter * cell_size
+            })
+            .collect();
+        Self::auto_named(positions)
+    }
+
+    /// Returns the label associated to the puncture point.
+    pub const fn name(&self) -> char {
+        self.name
+    }
+}
+
+impl<L> PuncturePoint<L> {
+    /// Represents a puncture point in the plane, named by any label type.
+    ///
+    /// Unlike [`PuncturePoint::new`], the label is kept as it is.
+    pub const fn labelled(position: Vec2, label: L) -> Self {
         Self {
             position,
-            name: name.to_ascii_uppercase(),
+            name: label,
+            order: None,
+            shape: PunctureShape::Circle { radius: 0.0 },
+        }
+    }
+
+    /// Returns the label of the puncture point. For `char` labels, this is the same as [`PuncturePoint::name`].
+    pub const fn label(&self) -> &L {
+        &self.name
+    }
+
+    /// Makes this a cone point of the given order, so that winding `order` times around it is trivial.
+    #[must_use]
+    pub const fn with_order(mut self, order: u32) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Returns the order of the puncture point if it is a cone point, or `None` for an ordinary puncture.
+    pub const fn order(&self) -> Option<u32> {
+        self.order
+    }
+
+    /// Gives the puncture point the footprint `shape`, which paths are not tightened through.
+    #[must_use]
+    pub const fn with_shape(mut self, shape: PunctureShape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Returns the footprint of the puncture point.
+    pub const fn shape(&self) -> &PunctureShape {
+        &self.shape
+    }
+
+    /// Collapses clusters of puncture points closer than `min_distance` into one puncture point each.
+    ///
+    /// Clusters are chained: two puncture points are in the same cluster if they can be joined by
+    /// hops shorter than `min_distance`. Each cluster is represented by its first puncture point,
+    /// keeping its name and position, so names stay unique if they were unique in `points`.
+    /// The representatives are returned in their original order.
+    pub fn merge_close(points: &[Self], min_distance: f32) -> Vec<Self>
+    where
+        L: Clone,
+    {
+        let mut cluster: Vec<usize> = (0..points.len()).collect();
+        for i in 0..points.len() {
+            for j in 0..i {
+                if points[i].distance_to(points[j].position) < min_distance {
+                    let (from, to) = (cluster[i].max(cluster[j]), cluster[i].min(cluster[j]));
+                    for id in &mut cluster {
+                        if *id == from {
+                            *id = to;
+                        }
+                    }
+                }
+            }
         }
+        points
+            .iter()
+            .zip(&cluster)
+            .enumerate()
+            .filter(|(i, (_, id))| *i == **id)
+            .map(|(_, (point, _))| point.clone())
+            .collect()
     }
 
     /// Returns the position of the puncture point in 2D.
@@ -104,12 +413,33 @@ impl PuncturePoint {
         &self.position
     }
 
-    /// Returns the label associated to the puncture point.
-    pub const fn name(&self) -> char {
-        self.name
+    /// Returns the distance from the puncture point to `point`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use glam::Vec2;
+    /// use charred_path::piecewise_linear::PuncturePoint;
+    ///
+    /// let puncture_point = PuncturePoint::new(Vec2::new(1.0, 1.0), 'A');
+    /// assert_eq!(puncture_point.distance_to(Vec2::new(4.0, 5.0)), 5.0);
+    /// assert_eq!(puncture_point.distance_squared_to(Vec2::new(4.0, 5.0)), 25.0);
+    /// ```
+    pub fn distance_to(&self, point: Vec2) -> f32 {
+        self.position.distance(point)
+    }
+
+    /// Returns the squared distance from the puncture point to `point`.
+    ///
+    /// This avoids a square root, so prefer it for comparisons.
+    pub fn distance_squared_to(&self, point: Vec2) -> f32 {
+        self.position.distance_squared(point)
     }
 
     /// Checks if the puncture point is inside a triangle defined by three points.
+    ///
+    /// The whole boundary, vertices included, counts as inside, so a puncture point touching the triangle
+    /// keeps the middle node whichever edge it lies on.
     fn is_in_triangle(&self, p1: &Vec2, p2: &Vec2, p3: &Vec2) -> bool {
         let p = self.position();
         let denom = (p2.y - p3.y).mul_add(p1.x - p3.x, (p3.x - p2.x) * (p1.y - p3.y));
@@ -119,13 +449,16 @@ impl PuncturePoint {
         let a = (p2.y - p3.y).mul_add(p.x - p3.x, (p3.x - p2.x) * (p.y - p3.y)) / denom;
         let b = (p3.y - p1.y).mul_add(p.x - p3.x, (p1.x - p3.x) * (p.y - p3.y)) / denom;
         let c = 1.0 - a - b;
-        [a, b, c].iter().all(|x| (0.0..1.0).contains(x))
+        [a, b, c].iter().all(|x| (0.0..=1.0).contains(x))
     }
 
     /// Checks if the puncture point should be removed based on its position relative to a triangle.
+    ///
+    /// The node `p2` is also kept if the shortcut from `p1` to `p3` would cut through the footprint.
     fn should_remove(&self, p1: &Vec2, p2: &Vec2, p3: &Vec2) -> bool {
         let x = self.position().x;
         !(self.is_in_triangle(p1, p2, p3)
+            || self.shape.overlaps_segment(self.position, *p1, *p3)
             || ((p1.x..p2.x).contains(&x) && p2.x < p3.x && (x - p2.x).abs() < 1e-3)
             || ((p2.x..p1.x).contains(&x) && p3.x < p2.x && (x - p2.x).abs() < 1e-3))
         // || (*self.position() - *p2).length_squared() < 5.0 && (*self.position() - *p3).length_squared() < 20.0
@@ -136,24 +469,210 @@ impl PuncturePoint {
     /// Returns `Some(1)` if the line passes left -> right above the point,
     /// `Some(-1)` if the line passes right -> left above the point, and
     /// `None` otherwise.
-    fn winding_update(&self, start: &Vec2, end: &Vec2) -> Option<i32> {
-        let position = self.position();
-        let cross_product = (end.y - start.y).mul_add(
-            position.x - start.x,
-            -((position.y - start.y) * (end.x - start.x)),
-        );
-        // Check if position is below the line segment
-        if cross_product > 0. && (start.x..end.x).contains(&position.x) {
-            return Some(1);
-        }
-        if cross_product < 0. && (end.x..start.x).contains(&position.x) {
-            return Some(-1);
-        }
+    ///
+    /// Segments passing within `epsilon` of the point are treated as not crossing, see [`PathType::with_winding_epsilon`].
+    fn winding_update(&self, start: &Vec2, end: &Vec2, epsilon: f32) -> Option<i32> {
+        winding_update(self.position(), start, end, epsilon)
+    }
+}
+
+/// Computes the winding update of the line segment from `start` to `end` around `position`.
+///
+/// Returns `Some(1)` if the line passes left -> right above the point,
+/// `Some(-1)` if the line passes right -> left above the point, and
+/// `None` otherwise, including when the line passes within `epsilon` of the point.
+fn winding_update(position: &Vec2, start: &Vec2, end: &Vec2, epsilon: f32) -> Option<i32> {
+    // The cross product is the distance from the line scaled by the segment length
+    winding_update_within(position, start, end, epsilon * start.distance(*end))
+}
+
+/// How many travelled segments [`PathType::compute_segment_letters`] tests against all puncture points at a time.
+///
+/// Blocks of this size stay in cache while the puncture points are looped over.
+const CROSSING_BLOCK: usize = 256;
+
+/// Computes the winding update like [`winding_update`], given the `tolerance` on the cross product,
+/// which is `epsilon` times the length of the segment.
+fn winding_update_within(position: &Vec2, start: &Vec2, end: &Vec2, tolerance: f32) -> Option<i32> {
+    // Most segments are not above the point at all, which is cheaper to check first
+    let left_to_right = (start.x..end.x).contains(&position.x);
+    if !left_to_right && !(end.x..start.x).contains(&position.x) {
+        return None;
+    }
+    let cross_product = (end.y - start.y).mul_add(
+        position.x - start.x,
+        -((position.y - start.y) * (end.x - start.x)),
+    );
+    // Check if position is below the line segment
+    if left_to_right && cross_product > tolerance {
+        Some(1)
+    } else if !left_to_right && cross_product < -tolerance {
+        Some(-1)
+    } else {
         None
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Component)]
+/// Computes where the line segment from `a0` to `a1` meets the line segment from `b0` to `b1`.
+///
+/// Both segments include their endpoints, so segments that only touch at an endpoint intersect there.
+/// When the segments are collinear and overlap, there is no single intersection point,
+/// so the first point of the overlap along `a0 -> a1` is returned.
+/// Zero-length segments are treated as points.
+/// Parallel segments that do not overlap, and segments that do not meet, return `None`.
+pub fn segment_intersection(a0: Vec2, a1: Vec2, b0: Vec2, b1: Vec2) -> Option<Vec2> {
+    let r = a1 - a0;
+    let s = b1 - b0;
+    let offset = b0 - a0;
+    let denominator = r.perp_dot(s);
+    if denominator != 0.0 {
+        let t = offset.perp_dot(s) / denominator;
+        let u = offset.perp_dot(r) / denominator;
+        return ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then(|| a0 + t * r);
+    }
+    // The segments are parallel, or at least one of them is a single point.
+    if r == Vec2::ZERO {
+        return point_on_segment(a0, b0, b1).then_some(a0);
+    }
+    if offset.perp_dot(r) != 0.0 {
+        return None;
+    }
+    // The segments lie on the same line; project `b` onto `a` and clip to `a`.
+    let t0 = offset.dot(r) / r.length_squared();
+    let t1 = t0 + s.dot(r) / r.length_squared();
+    let start = t0.min(t1).max(0.0);
+    let end = t0.max(t1).min(1.0);
+    (start <= end).then(|| a0 + start * r)
+}
+
+/// Whether `point` lies on the line segment from `start` to `end`, endpoints included.
+fn point_on_segment(point: Vec2, start: Vec2, end: Vec2) -> bool {
+    let direction = end - start;
+    if direction == Vec2::ZERO {
+        return point == start;
+    }
+    let offset = point - start;
+    offset.perp_dot(direction) == 0.0
+        && (0.0..=direction.length_squared()).contains(&offset.dot(direction))
+}
+
+/// The distance from `point` to the segment from `start` to `end`.
+fn segment_distance(point: Vec2, start: Vec2, end: Vec2) -> f32 {
+    let direction = end - start;
+    let t = if direction == Vec2::ZERO {
+        0.0
+    } else {
+        ((point - start).dot(direction) / direction.length_squared()).clamp(0.0, 1.0)
+    };
+    point.distance(start + t * direction)
+}
+
+/// A turtle-graphics style motion command, see [`PLPath::to_turn_commands`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum TurnCommand {
+    /// Move forward along the current heading by the given distance.
+    Forward(f32),
+    /// Rotate the heading by the given angle in radians, counterclockwise if positive.
+    Turn(f32),
+}
+
+/// Whether a path is treated as a loop, closed back to its start, or as an open journey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum Openness {
+    /// The path is closed by a straight segment from its end back to its start.
+    #[default]
+    Loop,
+    /// Only the segments of the path itself are used.
+    ///
+    /// The word of an open path is not an element of the fundamental group, since the path is not a loop,
+    /// but it is still a useful record of the puncture points the path passed over.
+    Open,
+}
+
+/// Which direction around a puncture point is written as the generator itself, e.g. the lowercase letter.
+///
+/// This only changes how words are written: [`PathType::letters`] and winding numbers
+/// always count clockwise as `+1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub enum OrientationConvention {
+    /// Going clockwise is the generator, written lowercase for `char` labels.
+    #[default]
+    ClockwisePositive,
+    /// Going counterclockwise is the generator, written lowercase for `char` labels.
+    CounterclockwisePositive,
+}
+
+/// Which way a loop goes around, see [`PLPath::orientation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Clockwise,
+    CounterClockwise,
+    /// The loop encloses no area and does not turn around, e.g. it doubles back on itself.
+    Degenerate,
+}
+
+/// The criterion used by [`PathType::relevant_punctures`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Relevance {
+    /// Puncture points the loop winds around a nonzero number of times.
+    #[default]
+    Winding,
+    /// Puncture points inside the axis-aligned bounding box of the path, boundary included.
+    BoundingBox,
+    /// Puncture points appearing in the reduced word. Filling in all the others leaves the word unchanged.
+    Word,
+}
+
+/// The rule used to decide whether a point is enclosed by a loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+    /// A point is enclosed if the loop winds around it a nonzero number of times.
+    /// This matches the homotopy type of the loop.
+    #[default]
+    NonZero,
+    /// A point is enclosed if the loop crosses over it an odd number of times.
+    EvenOdd,
+}
+
+/// Periods of a world that wraps around, like a cylinder or a torus.
+///
+/// When an axis has a period, a step between two nodes is taken the short way around:
+/// a step longer than half the period is treated as leaving one edge of the world and
+/// reappearing at the opposite edge, rather than as a long segment across the world.
+///
+/// Words only record crossings over puncture points, so going all the way around
+/// the wrapped world is not a letter of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct Wrap {
+    /// The width of the world, if it wraps horizontally.
+    pub x: Option<f32>,
+    /// The height of the world, if it wraps vertically.
+    pub y: Option<f32>,
+}
+
+impl Wrap {
+    /// Returns the step from `start` to `end` taken the short way around, if that crosses a wrap boundary.
+    fn wrapped_step(&self, start: Vec2, end: Vec2) -> Option<Vec2> {
+        let shorten = |delta: f32, period: Option<f32>| {
+            period
+                .filter(|period| delta.abs() > period / 2.0)
+                .map(|period| (-period).mul_add((delta / period).round(), delta))
+        };
+        let delta = end - start;
+        match (shorten(delta.x, self.x), shorten(delta.y, self.y)) {
+            (None, None) => None,
+            (x, y) => Some(Vec2::new(x.unwrap_or(delta.x), y.unwrap_or(delta.y))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
 pub struct PLPath {
     nodes: Vec<Vec2>,
 }
@@ -171,19 +690,31 @@ impl PLPath {
     ///
     /// ## Panics
     /// This will panic if `nodes` is empty.
+    #[cfg_attr(not(feature = "bevy"), allow(dead_code))]
     fn end(&self) -> &Vec2 {
         self.nodes.last().expect("Couldn't get the end point")
     }
 
-    ///
+    /// Appends a node to the end of the path.
     fn push(&mut self, position: &Vec2) {
         self.nodes.push(*position);
     }
     /// Appends the XY-position of a Transform
+    #[cfg(feature = "bevy")]
     pub fn push_transform(&mut self, transform: Transform) {
         self.nodes.push(transform.translation.truncate());
     }
 
+    /// Appends the XY-position of each Transform, in order.
+    #[cfg(feature = "bevy")]
+    pub fn push_transforms(&mut self, transforms: impl IntoIterator<Item = Transform>) {
+        self.nodes.extend(
+            transforms
+                .into_iter()
+                .map(|transform| transform.translation.truncate()),
+        );
+    }
+
     /// A new path from a list of nodes.
     pub fn new(nodes: impl Into<Vec<Vec2>>) -> Self {
         Self {
@@ -191,6 +722,18 @@ impl PLPath {
         }
     }
 
+    /// A new explicitly closed path from a list of nodes: the first node is appended
+    /// to the end, unless the last node already equals it.
+    pub fn closed(nodes: impl Into<Vec<Vec2>>) -> Self {
+        let mut nodes = nodes.into();
+        if let (Some(&first), Some(&last)) = (nodes.first(), nodes.last()) {
+            if first != last {
+                nodes.push(first);
+            }
+        }
+        Self { nodes }
+    }
+
     /// A straight line path from start to end.
     pub fn line(start: Vec2, end: Vec2) -> Self {
         Self {
@@ -207,6 +750,34 @@ impl PLPath {
         }
     }
 
+    /// Returns the path mirrored across the vertical line `x = axis_x`.
+    ///
+    /// Reflection reverses orientation: with the punctures mirrored as well, every winding number
+    /// changes sign, so the loop's word becomes its orientation-flipped form.
+    pub fn reflect_x(&self, axis_x: f32) -> Self {
+        Self {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|node| Vec2::new(axis_x.mul_add(2.0, -node.x), node.y))
+                .collect(),
+        }
+    }
+
+    /// Returns the path mirrored across the horizontal line `y = axis_y`.
+    ///
+    /// As with [`PLPath::reflect_x`], the loop's word becomes its orientation-flipped form
+    /// with respect to the mirrored punctures.
+    pub fn reflect_y(&self, axis_y: f32) -> Self {
+        Self {
+            nodes: self
+                .nodes
+                .iter()
+                .map(|node| Vec2::new(node.x, axis_y.mul_add(2.0, -node.y)))
+                .collect(),
+        }
+    }
+
     /// Returns a PLPath whose nodes are `self.nodes` concatenated by `other.nodes`.
     pub fn concatenate(&self, other: &Self) -> Self {
         let mut nodes = self.nodes.clone();
@@ -214,214 +785,4758 @@ impl PLPath {
         Self { nodes }
     }
 
-    /// An iterable containing each linear component of the path as a Segment2d.
-    /// Used to display the PL path as a loop for debugging purposes.
-    fn to_segment2d_iter(&self) -> impl Iterator<Item = (Segment2d, Vec2)> + '_ {
-        let last = if self.start() != self.end() {
-            Some(Segment2d::from_points(*self.end(), *self.start()))
-        } else {
-            None
-        };
-        self.nodes
-            .windows(2)
-            .filter_map(|pair| {
-                let point1 = pair[0];
-                let point2 = pair[1];
-                if point1 == point2 {
-                    None
-                } else {
-                    let segment = Segment2d::from_points(point1, point2);
-                    Some(segment)
-                }
-            })
-            .chain(last)
+    /// Returns the signed number of times the path, closed back to its start, winds around `point`.
+    ///
+    /// Clockwise traversal counts as `+1`.
+    pub fn winding_number(&self, point: &Vec2) -> i32 {
+        self.closed_segments()
+            .filter_map(|(start, end)| winding_update(point, start, end, 0.0))
+            .sum()
     }
-}
-
-/// Represents the homotopy type of a path in a punctured plane.
-///
-/// The `PathType` struct encapsulates the current path, puncture points, and the word representation
-/// of the homotopy type. It provides methods to update the path and retrieve the word representation.
-///
-/// # Fields
-///
-/// - `current_path`: The current path represented as a `PLPath` (piecewise linear path).
-/// - `puncture_points`: A shared reference to an array of `PuncturePoint` objects representing the puncture points in the plane.
-/// - `word`: The word representation of the homotopy type, which is automatically updated whenever the path is modified.
-///
-/// # Examples
-///
-/// ```
-/// use your_library::{PathType, PLPath, PuncturePoint};
-/// use std::sync::Arc;
-///
-/// let puncture_points = vec![
-///     PuncturePoint { position: (0.0, 0.0), name: 'A' },
-///     PuncturePoint { position: (1.0, 1.0), name: 'B' },
-/// ];
-/// let puncture_points = Arc::new(puncture_points);
-///
-/// let initial_path = PLPath::new();
-/// let mut path_type = PathType {
-///     current_path: initial_path,
-///     puncture_points,
-///     word: String::new(),
-/// };
-///
-/// // Update the path
-/// let new_path = PLPath::from_points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
-/// path_type.update_path(new_path);
-///
-/// // Get the updated word representation
-/// println!("Word representation: {}", path_type.word());
-/// ```
-#[derive(Debug, Clone, Component)]
-pub struct PathType {
-    current_path: PLPath,
-    puncture_points: Arc<[PuncturePoint]>,
-    word: String,
-}
 
-impl PathType {
-    pub fn word_as_str(&self) -> &str {
-        &self.word
+    /// Returns the winding number of the path, closed back to its start, around each of `points`.
+    ///
+    /// This makes a single sweep over the segments, which is cheaper than calling
+    /// [`PLPath::winding_number`] once per point.
+    pub fn winding_field(&self, points: &[Vec2]) -> Vec<i32> {
+        let mut field = vec![0; points.len()];
+        for (start, end) in self.closed_segments() {
+            for (winding, point) in field.iter_mut().zip(points) {
+                if let Some(update) = winding_update(point, start, end, 0.0) {
+                    *winding += update;
+                }
+            }
+        }
+        field
     }
-    pub fn word(&self) -> String {
-        self.word.clone()
+
+    /// Checks if `point` is enclosed by the path, closed back to its start, under the given fill rule.
+    pub fn contains_point(&self, point: &Vec2, fill_rule: FillRule) -> bool {
+        match fill_rule {
+            FillRule::NonZero => self.winding_number(point) != 0,
+            FillRule::EvenOdd => {
+                self.closed_segments()
+                    .filter(|(start, end)| winding_update(point, start, end, 0.0).is_some())
+                    .count()
+                    % 2
+                    == 1
+            }
+        }
     }
 
-    pub fn new(start: Vec2, puncture_points: Vec<PuncturePoint>) -> Self {
-        Self {
-            current_path: PLPath::new(vec![start]),
-            puncture_points: puncture_points.into(),
-            word: String::new(),
+    /// Returns where the path, closed back to its start, crosses the ray from `origin` along `direction`,
+    /// in path order, with the sign of each crossing.
+    ///
+    /// Signs follow [`PLPath::winding_number`]: a crossing counts `+1` if the path crosses the ray clockwise
+    /// around `origin`, so the signs add up to the winding number around `origin` for any ray.
+    /// With `direction` pointing up, these are exactly the crossings counted by the word.
+    /// A zero `direction` has no crossings.
+    pub fn ray_crossings(&self, origin: Vec2, direction: Vec2) -> Vec<(Vec2, i32)> {
+        if direction == Vec2::ZERO {
+            return Vec::new();
         }
+        let is_left = |point: Vec2| direction.perp_dot(point - origin) > 0.0;
+        self.closed_segments()
+            .filter(|(start, end)| is_left(**start) != is_left(**end))
+            .filter_map(|(start, end)| {
+                let step = *end - *start;
+                let distance = (*start - origin).perp_dot(step) / direction.perp_dot(step);
+                (distance >= 0.0).then(|| {
+                    let crossing = origin + direction * distance;
+                    (crossing, step.perp_dot(direction).signum() as i32)
+                })
+            })
+            .collect()
     }
 
-    pub fn from_path(path: PLPath, puncture_points: Arc<[PuncturePoint]>) -> Self {
-        let mut path_type = Self {
-            current_path: path,
-            puncture_points,
-            word: String::new(),
-        };
-        path_type.update_word();
-        path_type
+    /// Returns the signed turning angle, in radians, at node `index` of the path closed back to its start.
+    ///
+    /// Positive angles turn counterclockwise and negative angles turn clockwise.
+    /// A final node equal to the start is treated as the start itself.
+    /// Returns `None` if `index` is out of bounds or the node coincides with a neighbour.
+    pub fn angle_at(&self, index: usize) -> Option<f32> {
+        let nodes = self.loop_nodes();
+        let node = *nodes.get(index)?;
+        let previous = nodes[(index + nodes.len() - 1) % nodes.len()];
+        let next = nodes[(index + 1) % nodes.len()];
+        let (incoming, outgoing) = (node - previous, next - node);
+        if incoming == Vec2::ZERO || outgoing == Vec2::ZERO {
+            return None;
+        }
+        Some(incoming.angle_between(outgoing))
     }
 
-    #[must_use]
-    pub fn concatenate(&self, other: &PLPath) -> Self {
-        Self::from_path(
-            self.current_path.concatenate(other),
-            self.puncture_points.clone(),
-        )
+    /// The Hausdorff distance between the two paths as polylines, measured at their nodes:
+    /// the largest distance from a node of either path to the other path.
+    ///
+    /// Paths are not closed back to their start. Returns `f32::INFINITY` if exactly one path is empty.
+    pub fn hausdorff_distance(&self, other: &Self) -> f32 {
+        self.directed_distance(other)
+            .max(other.directed_distance(self))
     }
 
-    fn pop(&mut self) -> Option<Vec2> {
-        self.current_path.nodes.pop()
+    /// The largest distance from a node of `self` to the polyline `other`.
+    fn directed_distance(&self, other: &Self) -> f32 {
+        let distance_to_other = |point: Vec2| match &other.nodes[..] {
+            [] => f32::INFINITY,
+            [node] => point.distance(*node),
+            nodes => nodes
+                .windows(2)
+                .map(|pair| segment_distance(point, pair[0], pair[1]))
+                .fold(f32::INFINITY, f32::min),
+        };
+        self.nodes
+            .iter()
+            .map(|node| distance_to_other(*node))
+            .fold(0.0, f32::max)
     }
 
-    /// Appends a 2d position to the end of the current path.
-    pub fn push(&mut self, point: &Vec2) {
-        if let [.., p1, p2] = &self.current_path.nodes[..] {
-            if should_remove(p1, p2, point, &self.puncture_points) {
-                self.pop();
-                self.push(point);
-            } else {
-                self.current_path.push(point);
+    /// Removes nodes as long as the [Hausdorff distance](PLPath::hausdorff_distance)
+    /// to the original path stays at most `max_error`.
+    ///
+    /// Nodes are tried in order, each removal being checked against the original path,
+    /// and the first and last nodes are always kept.
+    pub fn simplify_hausdorff(&self, max_error: f32) -> Self {
+        let mut simplified = self.clone();
+        let mut index = 1;
+        while index + 1 < simplified.nodes.len() {
+            let removed = simplified.nodes.remove(index);
+            if self.hausdorff_distance(&simplified) > max_error {
+                simplified.nodes.insert(index, removed);
+                index += 1;
             }
-        } else {
-            self.current_path.push(point);
         }
-        self.update_word();
+        simplified
     }
 
-    /// Updates the word representing the homotopy type of the path.
-    /// Returns the updated word.
-    pub fn update_word(&mut self) -> String {
-        let mut word = String::new();
-        let full_loop: Vec<&Vec2> = self
-            .current_path
+    /// A cheap hash of the nodes, for caches to tell when the path actually changed.
+    ///
+    /// This is a 64-bit FNV-1a hash of the bit patterns of the coordinates, so it is the same across runs
+    /// and platforms. Since bits are hashed rather than values, `0.0` and `-0.0` hash differently,
+    /// and NaN coordinates hash by their payload, so equal paths with NaNs may not hash equally.
+    pub fn geometry_hash(&self) -> u64 {
+        const OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01B3;
+        self.nodes
+            .iter()
+            .flat_map(|node| [node.x, node.y])
+            .flat_map(|coordinate| coordinate.to_bits().to_le_bytes())
+            .fold(OFFSET_BASIS, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+            })
+    }
+
+    /// A coarse summary of the shape of the path, for finding near-duplicates in large collections of paths.
+    ///
+    /// The path is resampled evenly by arc length and the turns between consecutive steps are counted
+    /// into a histogram of `bins` equal ranges of angle, the first centered on going straight.
+    /// Each bin holds the share of the turns falling in it, rounded to eighths.
+    /// The signature ignores where the path is, its size and its rotation, and is unchanged by small noise
+    /// unless a turn lands close to the edge of a bin. Paths with equal signatures are only candidates,
+    /// to be compared exactly, e.g. by [`PLPath::hausdorff_distance`].
+    pub fn signature(&self, bins: usize) -> Vec<u8> {
+        /// How many nodes the path is resampled to before its turns are measured.
+        const SAMPLES: usize = 32;
+        /// How many levels the share of each bin is rounded to.
+        const LEVELS: f32 = 8.0;
+        if bins == 0 {
+            return Vec::new();
+        }
+        let turns: Vec<f32> = self
+            .resample(SAMPLES)
+            .nodes
+            .windows(3)
+            .filter_map(|triple| {
+                let (incoming, outgoing) = (triple[1] - triple[0], triple[2] - triple[1]);
+                (incoming != Vec2::ZERO && outgoing != Vec2::ZERO)
+                    .then(|| incoming.angle_between(outgoing))
+            })
+            .collect();
+        let mut histogram = vec![0_usize; bins];
+        for turn in &turns {
+            let bin = (turn / std::f32::consts::TAU * bins as f32).round() as isize;
+            histogram[bin.rem_euclid(bins as isize) as usize] += 1;
+        }
+        histogram
+            .into_iter()
+            .map(|count| (count as f32 / turns.len().max(1) as f32 * LEVELS).round() as u8)
+            .collect()
+    }
+
+    /// Describes the path as turtle-graphics style commands, for exporting to motion-planning tools.
+    ///
+    /// The turtle starts at the first node heading along the positive x-axis, so the first command is
+    /// a [`TurnCommand::Turn`] setting the initial heading. Each segment is then a turn followed by a
+    /// [`TurnCommand::Forward`]. The path is not closed back to its start, and repeated nodes are skipped.
+    pub fn to_turn_commands(&self) -> Vec<TurnCommand> {
+        let mut heading = Vec2::X;
+        let mut commands = Vec::new();
+        for pair in self.nodes.windows(2) {
+            let step = pair[1] - pair[0];
+            if step == Vec2::ZERO {
+                continue;
+            }
+            commands.push(TurnCommand::Turn(heading.angle_between(step)));
+            commands.push(TurnCommand::Forward(step.length()));
+            heading = step;
+        }
+        commands
+    }
+
+    /// Renders the path as an SVG polyline that draws itself over `duration_secs`, for sharing demos.
+    ///
+    /// The stroke is animated with `stroke-dasharray`/`stroke-dashoffset`. The y-axis is flipped so the
+    /// picture matches the world, where y points up, and the view box fits the path with a small margin.
+    /// The path is not closed back to its start.
+    pub fn to_animated_svg(&self, duration_secs: f32) -> String {
+        use std::fmt::Write;
+
+        let points: Vec<Vec2> = self
             .nodes
             .iter()
-            .chain(std::iter::once(self.current_path.start()))
+            // `0.0 - y` rather than `-y`, so the axis is not written out as `-0`
+            .map(|node| Vec2::new(node.x, 0.0 - node.y))
             .collect();
-        for segment in full_loop.windows(2) {
-            let (start, end) = (segment[0], segment[1]);
-            let punctures: Vec<&PuncturePoint> = match start.x.partial_cmp(&end.x) {
-                Some(Ordering::Less) => self.puncture_points.iter().collect(),
-                Some(Ordering::Greater) => self
-                    .puncture_points
-                    .iter()
-                    //.rev()
-                    .collect(),
-                _ => continue,
+        let (min, max) = points.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), point| (min.min(*point), max.max(*point)),
+        );
+        let (min, max) = if points.is_empty() {
+            (Vec2::ZERO, Vec2::ZERO)
+        } else {
+            (min, max)
+        };
+        let margin = ((max - min).max_element() * 0.05).max(1.0);
+        let (origin, size) = (min - margin, max - min + 2.0 * margin);
+        let length: f32 = self
+            .nodes
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum();
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">\n",
+            origin.x, origin.y, size.x, size.y
+        );
+        let points: Vec<String> = points
+            .iter()
+            .map(|point| format!("{},{}", point.x, point.y))
+            .collect();
+        let _ = writeln!(
+            svg,
+            "  <polyline points=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"2\" \
+             vector-effect=\"non-scaling-stroke\" stroke-dasharray=\"{length}\" stroke-dashoffset=\"{length}\">",
+            points.join(" ")
+        );
+        let _ = writeln!(
+            svg,
+            "    <animate attributeName=\"stroke-dashoffset\" from=\"{length}\" to=\"0\" dur=\"{duration_secs}s\" fill=\"freeze\"/>"
+        );
+        svg.push_str("  </polyline>\n</svg>\n");
+        svg
+    }
+
+    /// Returns the signed area enclosed by the path, closed back to its start, by the shoelace formula.
+    ///
+    /// The area is positive for counterclockwise paths.
+    pub fn signed_area(&self) -> f32 {
+        self.closed_segments()
+            .map(|(start, end)| start.perp_dot(*end))
+            .sum::<f32>()
+            / 2.0
+    }
+
+    /// Returns the total signed angle the path, closed back to its start, turns through at its corners,
+    /// counterclockwise turns counting as positive.
+    ///
+    /// This is `2π` times the turning number of the loop, e.g. `2π` for a counterclockwise simple loop.
+    /// Repeated nodes are skipped.
+    pub fn total_turning(&self) -> f32 {
+        let mut nodes: Vec<Vec2> = self.loop_nodes().to_vec();
+        nodes.dedup();
+        if nodes.len() > 1 && nodes.first() == nodes.last() {
+            nodes.pop();
+        }
+        if nodes.len() < 3 {
+            return 0.0;
+        }
+        (0..nodes.len())
+            .map(|index| {
+                let previous = nodes[(index + nodes.len() - 1) % nodes.len()];
+                let next = nodes[(index + 1) % nodes.len()];
+                (nodes[index] - previous).angle_between(next - nodes[index])
+            })
+            .sum()
+    }
+
+    /// Returns which way the path, closed back to its start, goes around, or `None` if it has fewer than
+    /// three distinct nodes.
+    ///
+    /// The sign of [`PLPath::signed_area`] decides, unless the area is tiny for the size of the loop, as
+    /// for thin slivers whose area is lost to rounding. Then the sign of [`PLPath::total_turning`] decides,
+    /// and if the loop does not turn around either, it is [`Orientation::Degenerate`].
+    pub fn orientation(&self) -> Option<Orientation> {
+        const AREA_EPSILON: f32 = 1e-6;
+        let mut distinct = self.nodes.clone();
+        distinct.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        distinct.dedup();
+        if distinct.len() < 3 {
+            return None;
+        }
+        let perimeter: f32 = self
+            .closed_segments()
+            .map(|(start, end)| start.distance(*end))
+            .sum();
+        let area = self.signed_area();
+        let turning = self.total_turning();
+        let counterclockwise = if area.abs() > AREA_EPSILON * perimeter * perimeter {
+            area > 0.0
+        } else if turning.abs() > std::f32::consts::PI {
+            turning > 0.0
+        } else {
+            return Some(Orientation::Degenerate);
+        };
+        Some(if counterclockwise {
+            Orientation::CounterClockwise
+        } else {
+            Orientation::Clockwise
+        })
+    }
+
+    /// Checks if the path, closed back to its start, bounds a convex region.
+    ///
+    /// This holds when every corner turns the same way and the path turns around exactly once.
+    /// Collinear and repeated nodes are ignored. Paths with fewer than three distinct corners are not convex.
+    pub fn is_convex(&self) -> bool {
+        const EPSILON: f32 = 1e-4;
+        let turns: Vec<f32> = (0..self.loop_nodes().len())
+            .filter_map(|index| self.angle_at(index))
+            .filter(|angle| angle.abs() > EPSILON)
+            .collect();
+        if turns.len() < 3 {
+            return false;
+        }
+        let same_sign = turns
+            .iter()
+            .all(|angle| angle.signum() == turns[0].signum());
+        let total: f32 = turns.iter().sum();
+        same_sign && (total.abs() - std::f32::consts::TAU).abs() < EPSILON.sqrt()
+    }
+
+    /// The nodes of the path, without a final node equal to the start.
+    fn loop_nodes(&self) -> &[Vec2] {
+        match &self.nodes[..] {
+            [first, rest @ .., last] if first == last && !rest.is_empty() => {
+                &self.nodes[..self.nodes.len() - 1]
+            }
+            nodes => nodes,
+        }
+    }
+
+    /// Pairs of consecutive nodes, followed by the segment closing the path back to its start.
+    fn closed_segments(&self) -> impl Iterator<Item = (&Vec2, &Vec2)> + '_ {
+        self.segments(Openness::Loop)
+    }
+
+    /// Pairs of consecutive nodes. For [`Openness::Loop`], these are followed by the segment
+    /// closing the path back to its start.
+    fn segments(&self, openness: Openness) -> impl Iterator<Item = (&Vec2, &Vec2)> + '_ {
+        let closing = self.nodes.first().filter(|_| openness == Openness::Loop);
+        self.nodes
+            .iter()
+            .zip(self.nodes.iter().skip(1).chain(closing))
+    }
+
+    /// Returns the points where the path crosses itself, with or without the segment closing it.
+    ///
+    /// Consecutive segments always share a node, so they are not checked against each other,
+    /// and repeated nodes are ignored. A crossing through a node is found for every pair of
+    /// non-consecutive segments meeting there.
+    pub fn self_intersections(&self, openness: Openness) -> Vec<Vec2> {
+        let segments: Vec<(Vec2, Vec2)> = self
+            .segments(openness)
+            .filter(|(start, end)| start != end)
+            .map(|(start, end)| (*start, *end))
+            .collect();
+        let last = segments.len().saturating_sub(1);
+        let mut points = Vec::new();
+        for (i, (a0, a1)) in segments.iter().enumerate() {
+            for (j, (b0, b1)) in segments.iter().enumerate().skip(i + 2) {
+                if i == 0 && j == last && segments[last].1 == segments[0].0 {
+                    continue;
+                }
+                points.extend(segment_intersection(*a0, *a1, *b0, *b1));
+            }
+        }
+        points
+    }
+
+    /// Returns `count` nodes evenly spaced by arc length along the path, from its start to its end.
+    ///
+    /// The path is not closed back to its start. A single node is placed at the start.
+    pub fn resample(&self, count: usize) -> Self {
+        let Some(&start) = self.nodes.first() else {
+            return Self::new(vec![]);
+        };
+        if count < 2 {
+            return Self::new(vec![start; count]);
+        }
+        let lengths: Vec<f32> = self
+            .nodes
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .collect();
+        let total: f32 = lengths.iter().sum();
+        let mut nodes = vec![start];
+        let (mut segment, mut travelled) = (0, 0.0);
+        for i in 1..count {
+            let target = total * i as f32 / (count - 1) as f32;
+            while segment + 1 < lengths.len() && travelled + lengths[segment] < target {
+                travelled += lengths[segment];
+                segment += 1;
+            }
+            let node = match lengths.get(segment) {
+                Some(&length) if length > 0.0 => {
+                    let t = ((target - travelled) / length).clamp(0.0, 1.0);
+                    self.nodes[segment].lerp(self.nodes[segment + 1], t)
+                }
+                _ => self.nodes[(segment + 1).min(self.nodes.len() - 1)],
+            };
+            nodes.push(node);
+        }
+        Self { nodes }
+    }
+
+    /// Returns the cells of a grid of `cell_size` cells, with cell `(0, 0)` at the origin, that the path passes through.
+    ///
+    /// This is a supercover rasterization of the path, which is not closed back to its start:
+    /// every cell that a segment touches is included, so a segment passing exactly through the corner
+    /// of a cell also covers both cells next to the corner.
+    pub fn covered_cells(&self, cell_size: Vec2) -> HashSet<IVec2> {
+        let nodes: Vec<Vec2> = self.nodes.iter().map(|node| *node / cell_size).collect();
+        let mut cells: HashSet<IVec2> = nodes
+            .first()
+            .map(|node| node.floor().as_ivec2())
+            .into_iter()
+            .collect();
+        for pair in nodes.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            let mut cell = start.floor().as_ivec2();
+            let end_cell = end.floor().as_ivec2();
+            let step = (end_cell - cell).signum();
+            let mut remaining = (end_cell - cell).abs();
+            let direction = end - start;
+            // The parameter along the segment at which it next crosses a vertical or horizontal grid line.
+            let next_crossing = |cell: IVec2, axis: usize| match step[axis] {
+                0 => f32::INFINITY,
+                1 => (cell[axis] as f32 + 1.0 - start[axis]) / direction[axis],
+                _ => (cell[axis] as f32 - start[axis]) / direction[axis],
             };
-            for puncture in punctures {
-                if let Some(n) = puncture.winding_update(start, end) {
-                    match n {
-                        1 => word.push(puncture.name.to_ascii_lowercase()),
-                        -1 => word.push(puncture.name.to_ascii_uppercase()),
-                        _ => {}
+            while remaining != IVec2::ZERO {
+                let (t_x, t_y) = (next_crossing(cell, 0), next_crossing(cell, 1));
+                if remaining.y == 0 || (remaining.x > 0 && t_x < t_y) {
+                    cell.x += step.x;
+                    remaining.x -= 1;
+                } else if remaining.x == 0 || t_y < t_x {
+                    cell.y += step.y;
+                    remaining.y -= 1;
+                } else {
+                    cells.insert(IVec2::new(cell.x + step.x, cell.y));
+                    cells.insert(IVec2::new(cell.x, cell.y + step.y));
+                    cell += step;
+                    remaining -= IVec2::ONE;
+                }
+                cells.insert(cell);
+            }
+        }
+        cells
+    }
+
+    /// Resamples the path with nodes closer together around sharp corners and farther apart along straight runs.
+    ///
+    /// The nodes of the path are kept, and the spacing next to each is between `max_spacing` for a straight
+    /// node and `min_spacing` for a sharp corner, approached faster for a larger `curvature_scale`.
+    /// Away from the nodes, the spacing grows gradually back up to `max_spacing`.
+    /// The path is not closed back to its start, so its first and last nodes count as straight.
+    pub fn resample_adaptive(
+        &self,
+        min_spacing: f32,
+        max_spacing: f32,
+        curvature_scale: f32,
+    ) -> Self {
+        /// How much the spacing may grow per unit of distance from a node.
+        const GROWTH: f32 = 0.5;
+        let spacing_at = |index: usize| {
+            let turn = match (index.checked_sub(1), self.nodes.get(index + 1)) {
+                (Some(previous), Some(next)) => {
+                    let node = self.nodes[index];
+                    let (incoming, outgoing) = (node - self.nodes[previous], *next - node);
+                    if incoming == Vec2::ZERO || outgoing == Vec2::ZERO {
+                        0.0
+                    } else {
+                        incoming.angle_between(outgoing).abs()
                     }
                 }
+                _ => 0.0,
+            };
+            let sharpness = 1.0 - (-curvature_scale * turn).exp();
+            (min_spacing - max_spacing).mul_add(sharpness, max_spacing)
+        };
+        let Some(&start) = self.nodes.first() else {
+            return Self::new(vec![]);
+        };
+        let mut nodes = vec![start];
+        for index in 1..self.nodes.len() {
+            let (from, to) = (self.nodes[index - 1], self.nodes[index]);
+            let length = from.distance(to);
+            let (from_spacing, to_spacing) = (spacing_at(index - 1), spacing_at(index));
+            // Bounded below so that degenerate spacings cannot produce endless samples.
+            let spacing = |along: f32| {
+                GROWTH
+                    .mul_add(along, from_spacing)
+                    .min(GROWTH.mul_add(length - along, to_spacing))
+                    .min(max_spacing)
+                    .max(min_spacing)
+                    .max(length * 1e-4)
+            };
+            let mut stops = Vec::new();
+            let mut along = 0.0;
+            loop {
+                along += spacing(along);
+                if along >= length {
+                    break;
+                }
+                stops.push(along);
             }
+            // The last step overshoots `to`, so every step is shrunk a little to land on it.
+            nodes.extend(stops.into_iter().map(|stop| from.lerp(to, stop / along)));
+            nodes.push(to);
         }
+        Self { nodes }
+    }
+
+    /// Checks if any node of the path lies outside `rect`, see [`PLPath::leaves_box`].
+    #[cfg(feature = "bevy")]
+    pub fn leaves_rect(&self, rect: Rect) -> bool {
+        self.leaves_box(rect.min, rect.max)
+    }
+
+    /// Returns the index of the first node of the path outside `rect`, if any, see [`PLPath::leaves_box`].
+    #[cfg(feature = "bevy")]
+    pub fn first_exit(&self, rect: Rect) -> Option<usize> {
+        self.first_exit_box(rect.min, rect.max)
+    }
+
+    /// Checks if any node of the path lies outside the axis-aligned box from `min` to `max`.
+    /// Nodes on the edge of the box are inside.
+    ///
+    /// Only the nodes are checked: a segment cutting a corner of the box between two nodes inside it does not leave.
+    pub fn leaves_box(&self, min: Vec2, max: Vec2) -> bool {
+        self.first_exit_box(min, max).is_some()
+    }
+
+    /// Returns the index of the first node of the path outside the axis-aligned box from `min` to `max`, if any,
+    /// see [`PLPath::leaves_box`].
+    pub fn first_exit_box(&self, min: Vec2, max: Vec2) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| !(node.cmpge(min).all() && node.cmple(max).all()))
+    }
+
+    /// Each segment of the path, closed back to its start, as a `Segment2d` with its center,
+    /// see [`PLPath::render_lines`].
+    #[cfg(feature = "bevy")]
+    pub fn render_primitives(&self) -> Vec<(Segment2d, Vec2)> {
+        self.render_lines()
+            .into_iter()
+            .map(|(start, end)| Segment2d::from_points(start, end))
+            .collect()
+    }
 
-        simplify_word(&mut word);
-        self.word = word.clone();
-        word
+    /// Each segment of the path, closed back to its start, as its start and end points.
+    ///
+    /// These are the lines drawn by `PathDebugPlugin`, for drawing the path with another renderer.
+    /// Repeated nodes are skipped, and paths with fewer than two nodes have no segments.
+    pub fn render_lines(&self) -> Vec<(Vec2, Vec2)> {
+        self.segments(Openness::Loop)
+            .filter(|(start, end)| start != end)
+            .map(|(start, end)| (*start, *end))
+            .collect()
     }
 }
 
-fn simplify_word(word: &mut String) {
-    let mut i = 0;
-    while i + 1 < word.len() {
-        let a = word.as_bytes()[i] as char;
-        let b = word.as_bytes()[i + 1] as char;
+/// A piecewise-linear path on integer coordinates, for paths traced on a grid.
+///
+/// Crossings are decided with exact integer arithmetic, so unlike [`PLPath`] there is no tolerance to tune:
+/// a segment passes over a puncture point exactly when the puncture point's column lies in the half-open
+/// range `[start.x, end.x)` (or `[end.x, start.x)`) and the point is strictly below the segment.
+/// Segments passing exactly through a puncture point, and vertical segments, contribute nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(serde::Serialize, serde::Deserialize))]
+pub struct PLPathI {
+    nodes: Vec<IVec2>,
+}
 
-        if a.to_ascii_uppercase() == b.to_ascii_uppercase() && a != b {
-            word.drain(i..i + 2);
-            i = i.saturating_sub(1);
-        } else {
-            i += 1;
+impl PLPathI {
+    /// Creates a new path from integer nodes.
+    pub fn new(nodes: impl Into<Vec<IVec2>>) -> Self {
+        Self {
+            nodes: nodes.into(),
+        }
+    }
+
+    /// Rounds each node of `path` to the nearest integer coordinates.
+    pub fn from_path_rounded(path: &PLPath) -> Self {
+        Self {
+            nodes: path
+                .nodes
+                .iter()
+                .map(|node| node.round().as_ivec2())
+                .collect(),
+        }
+    }
+
+    /// The nodes of the path.
+    pub fn nodes(&self) -> &[IVec2] {
+        &self.nodes
+    }
+
+    /// Appends a node to the end of the path.
+    ///
+    /// Unlike [`PathType::push`], no nodes are tightened away.
+    pub fn push(&mut self, node: IVec2) {
+        self.nodes.push(node);
+    }
+
+    /// Pairs of consecutive nodes, followed by the segment closing the path back to its start.
+    fn closed_segments(&self) -> impl Iterator<Item = (IVec2, IVec2)> + '_ {
+        self.nodes
+            .iter()
+            .copied()
+            .zip(self.nodes.iter().copied().cycle().skip(1))
+    }
+
+    /// Returns the signed number of times the path, closed back to its start, winds around `point`.
+    ///
+    /// Clockwise traversal counts as `+1`, as for [`PLPath::winding_number`].
+    pub fn winding_number(&self, point: IVec2) -> i32 {
+        self.closed_segments()
+            .filter_map(|(start, end)| exact_winding_update(point, start, end))
+            .sum()
+    }
+
+    /// Returns the freely reduced letters of the loop around the named `puncture_points`,
+    /// where `1` is clockwise and `-1` counterclockwise.
+    ///
+    /// When a segment passes over several puncture points, they are written in the order it sweeps across them.
+    pub fn letters<L: Label>(&self, puncture_points: &[(L, IVec2)]) -> Vec<(L, i32)> {
+        let mut letters = Vec::new();
+        for (start, end) in self.closed_segments() {
+            let direction = (end - start).as_i64vec2();
+            let mut crossings: Vec<(i64, &L, i32)> = puncture_points
+                .iter()
+                .filter_map(|(label, position)| {
+                    exact_winding_update(*position, start, end).map(|n| {
+                        let along = (*position - start).as_i64vec2().dot(direction);
+                        (along, label, n)
+                    })
+                })
+                .collect();
+            crossings.sort_by_key(|(along, _, _)| *along);
+            letters.extend(
+                crossings
+                    .into_iter()
+                    .map(|(_, label, n)| (label.clone(), n)),
+            );
         }
+        reduce_letters(&mut letters, |_| None);
+        letters
+    }
+
+    /// Returns the freely reduced word of the loop around the named `puncture_points`, see [`PLPathI::letters`].
+    pub fn word<L: Label>(&self, puncture_points: &[(L, IVec2)]) -> String {
+        render_letters(&self.letters(puncture_points))
+    }
+}
+
+impl From<&PLPathI> for PLPath {
+    fn from(path: &PLPathI) -> Self {
+        Self::new(
+            path.nodes
+                .iter()
+                .map(|node| node.as_vec2())
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+impl From<PLPathI> for PLPath {
+    fn from(path: PLPathI) -> Self {
+        Self::from(&path)
+    }
+}
+
+/// Morphs between the paths `a` and `b`, for `t` from `0.0` to `1.0`.
+///
+/// Both paths are resampled, see [`PLPath::resample`], to the larger of their node counts,
+/// and corresponding nodes are linearly interpolated. Even when `a` and `b` are homotopic,
+/// the intermediate paths may sweep over puncture points, so they need not be in the same homotopy class.
+pub fn lerp_path(a: &PLPath, b: &PLPath, t: f32) -> PLPath {
+    let count = a.nodes.len().max(b.nodes.len());
+    let (a, b) = (a.resample(count), b.resample(count));
+    PLPath::new(
+        a.nodes
+            .iter()
+            .zip(&b.nodes)
+            .map(|(a, b)| a.lerp(*b, t))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// The exact counterpart of [`winding_update`] for integer coordinates, with no tolerance.
+fn exact_winding_update(position: IVec2, start: IVec2, end: IVec2) -> Option<i32> {
+    let [position, start, end] = [position, start, end].map(|point| point.as_i64vec2());
+    let cross_product = (end - start).perp_dot(position - start);
+    if cross_product < 0 && (start.x..end.x).contains(&position.x) {
+        return Some(1);
+    }
+    if cross_product > 0 && (end.x..start.x).contains(&position.x) {
+        return Some(-1);
     }
+    None
+}
+
+/// Represents the homotopy type of a path in a punctured plane.
+///
+/// The `PathType` struct encapsulates the current path, puncture points, and the word representation
+/// of the homotopy type. It provides methods to update the path and retrieve the word representation.
+///
+/// # Fields
+///
+/// - `current_path`: The current path represented as a `PLPath` (piecewise linear path).
+/// - `puncture_points`: A shared reference to an array of `PuncturePoint` objects representing the puncture points in the plane.
+/// - `letters`: The reduced word of the homotopy type as `(label, exponent)` pairs, which is automatically updated whenever the path is modified.
+/// - `segment_letters`: The unreduced letters contributed by each segment of the path, updated along with `letters`.
+/// - `word`: `letters` written out as a string, see [`Label`].
+/// - `dirty`: Whether the nodes have changed since `word` was last computed.
+/// - `crossing_counts`: How many times each puncture point has been passed over by a pushed segment.
+/// - `openness`: Whether the word is computed for the path closed into a loop, or for the open path.
+/// - `wrap`: The periods of the world, if it wraps around.
+/// - `winding_epsilon`: How close a segment may pass to a puncture point before it no longer counts as crossing over it.
+/// - `avoid_punctures`: Whether pushed points inside a puncture point's shape are moved out of it.
+/// - `shared_punctures`: The handle the puncture points are taken from, if any, and the version last taken.
+/// - `orientation`: Which direction around a puncture point is written as the generator in `word`.
+/// - `defer_word`: Whether pushing leaves the word stale until the next [`PathType::update_word`], see `WordTimer`.
+/// - `word_history`: The most recent distinct words, oldest first, if a history length has been set.
+/// - `word_history_len`: The maximum number of words kept in `word_history`; `0` disables the history.
+/// - `timestamps`: When each node of `current_path` was pushed, in the same order as the nodes.
+///
+/// # Examples
+///
+/// ```
+/// use glam::Vec2;
+/// use charred_path::piecewise_linear::{PLPath, PathType, PuncturePoint};
+/// use std::sync::Arc;
+///
+/// let puncture_points: Arc<[PuncturePoint]> = vec![
+///     PuncturePoint::new(Vec2::new(0.0, 0.0), 'A'),
+///     PuncturePoint::new(Vec2::new(5.0, 0.0), 'B'),
+/// ]
+/// .into();
+///
+/// // A clockwise loop around `A`.
+/// let path = PLPath::new(vec![
+///     Vec2::new(-1.0, 1.0),
+///     Vec2::new(1.0, 1.0),
+///     Vec2::new(1.0, -1.0),
+///     Vec2::new(-1.0, -1.0),
+/// ]);
+/// let path_type = PathType::from_path(path, puncture_points);
+///
+/// assert_eq!(path_type.word_as_str(), "a");
+/// ```
+///
+/// Puncture points can be named by any [`Label`] type, in which case the path type is a `PathType<L>`.
+/// `PathType` on its own means `PathType<char>`.
+///
+/// With the `serialize` feature, the word is not serialized but recomputed when deserializing,
+/// and with `bevy` as well, `PathType` is reflected as a serializable value, so it can be saved in scenes.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "bevy", derive(Component))]
+#[cfg_attr(
+    feature = "serialize",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        from = "PathTypeData<L>",
+        into = "PathTypeData<L>",
+        bound(
+            serialize = "L: Label + serde::Serialize",
+            deserialize = "L: Label + serde::Deserialize<'de>"
+        )
+    )
+)]
+#[cfg_attr(
+    all(feature = "bevy", feature = "serialize"),
+    derive(bevy::reflect::Reflect),
+    reflect_value(
+        Component,
+        Serialize,
+        Deserialize,
+        where L: Label + serde::Serialize + for<'de> serde::Deserialize<'de>
+    )
+)]
+pub struct PathType<L = char> {
+    current_path: PLPath,
+    puncture_points: Arc<[PuncturePoint<L>]>,
+    letters: Vec<(L, i32)>,
+    segment_letters: Vec<Vec<(L, i32)>>,
+    word: String,
+    dirty: bool,
+    crossing_counts: HashMap<L, u32>,
+    openness: Openness,
+    wrap: Wrap,
+    winding_epsilon: f32,
+    avoid_punctures: bool,
+    defer_word: bool,
+    orientation: OrientationConvention,
+    shared_punctures: Option<(PuncturePointsHandle<L>, u64)>,
+    word_history: VecDeque<String>,
+    word_history_len: usize,
+    timestamps: Vec<Duration>,
 }
 
-/// This visualizes the piecewise-linear paths.
-fn debug_render_paths(path_types: Query<&PathType>, mut gizmos: Gizmos) {
-    for path_type in path_types.iter() {
-        if path_type.current_path.nodes.len() > 1 {
-            for segment in path_type.current_path.to_segment2d_iter() {
-                gizmos.primitive_2d(segment.0, segment.1, 0.0, Color::WHITE);
+impl<L: Label> PathType<L> {
+    pub fn word_as_str(&self) -> &str {
+        &self.word
+    }
+    pub fn word(&self) -> String {
+        self.word.clone()
+    }
+
+    /// Returns the reduced word as `(label, exponent)` letters, where `1` is clockwise and `-1` counterclockwise.
+    pub fn letters(&self) -> &[(L, i32)] {
+        &self.letters
+    }
+
+    /// Returns the letters contributed by each segment of the path, before reduction, in path order,
+    /// followed by the closing segment for [`Openness::Loop`].
+    ///
+    /// This is kept in sync with the word, so renderers can color segments without recomputing crossings.
+    pub fn segment_generators(&self) -> &[Vec<(L, i32)>] {
+        &self.segment_letters
+    }
+
+    /// Checks if the loops commute in the fundamental group, i.e. if the commutator `a b a^-1 b^-1`
+    /// of their words reduces to the empty word.
+    ///
+    /// In a free group this only happens when both loops are powers of a common loop.
+    /// Both loops are assumed to share their basepoint and puncture points.
+    pub fn commutes_with(&self, other: &Self) -> bool {
+        let inverse = |letters: &[(L, i32)]| {
+            letters
+                .iter()
+                .rev()
+                .map(|(label, exponent)| (label.clone(), -exponent))
+                .collect::<Vec<_>>()
+        };
+        let mut commutator = [
+            self.letters.clone(),
+            other.letters.clone(),
+            inverse(&self.letters),
+            inverse(&other.letters),
+        ]
+        .concat();
+        reduce_letters(&mut commutator, |label| {
+            self.puncture_points
+                .iter()
+                .find(|puncture| puncture.label() == label)
+                .and_then(PuncturePoint::order)
+        });
+        commutator.is_empty()
+    }
+
+    /// Returns the word with every puncture point not named in `labels` filled in, i.e. with their
+    /// generators dropped, reduced again.
+    ///
+    /// This is the image of the homotopy class under the map forgetting the other puncture points,
+    /// e.g. `abAB` restricted to `A` is trivial.
+    pub fn restricted_word(&self, labels: &[L]) -> String {
+        self.word_keeping(|label| labels.contains(label))
+    }
+
+    /// Returns the word with the puncture points named in `fill` filled in, i.e. with their generators
+    /// dropped, reduced again.
+    ///
+    /// This is the image of the homotopy class in the fundamental group of the plane punctured only
+    /// at the remaining puncture points, the complement of [`PathType::restricted_word`]:
+    /// e.g. `abAB` with `B` filled in is `aA`, which is trivial.
+    pub fn quotient_word(&self, fill: &[L]) -> String {
+        self.word_keeping(|label| !fill.contains(label))
+    }
+
+    /// Returns the word with only the generators whose labels satisfy `keep`, reduced again.
+    fn word_keeping(&self, keep: impl Fn(&L) -> bool) -> String {
+        let mut letters: Vec<(L, i32)> = self
+            .letters
+            .iter()
+            .filter(|(label, _)| keep(label))
+            .cloned()
+            .collect();
+        reduce_letters(&mut letters, |label| {
+            self.puncture_points
+                .iter()
+                .find(|puncture| puncture.label() == label)
+                .and_then(PuncturePoint::order)
+        });
+        self.render(&letters)
+    }
+
+    /// Returns the reduced word as a product of powers of the standard generator loops,
+    /// grouping runs of the same generator into one `(label, exponent)` factor.
+    ///
+    /// For example, `aabAA` is `a^2 b a^-2`: `[('A', 2), ('B', 1), ('A', -2)]`.
+    pub fn as_generator_product(&self) -> Vec<(L, i32)> {
+        let mut factors: Vec<(L, i32)> = Vec::new();
+        for (label, exponent) in &self.letters {
+            match factors.last_mut() {
+                Some((last, power)) if last == label => *power += exponent,
+                _ => factors.push((label.clone(), *exponent)),
             }
         }
+        factors
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Lazily yields the generators of the reduced word in order, as `(label, ±1)`, without allocating.
+    ///
+    /// A letter with exponent `n` is yielded `|n|` times.
+    pub fn generators_iter(&self) -> impl Iterator<Item = (L, i32)> + '_ {
+        self.letters.iter().flat_map(|(label, exponent)| {
+            std::iter::repeat_n(
+                (label.clone(), exponent.signum()),
+                exponent.unsigned_abs() as usize,
+            )
+        })
+    }
 
-    #[test]
-    fn test_is_point_in_triangle() {
-        let p1 = &Vec2::new(0.0, 0.0);
-        let p2 = &Vec2::new(4.0, 0.0);
-        let p3 = &Vec2::new(2.0, 4.0);
+    pub fn new(start: Vec2, puncture_points: Vec<PuncturePoint<L>>) -> Self {
+        Self {
+            current_path: PLPath::new(vec![start]),
+            puncture_points: puncture_points.into(),
+            letters: Vec::new(),
+            segment_letters: Vec::new(),
+            word: String::new(),
+            dirty: false,
+            crossing_counts: HashMap::new(),
+            openness: Openness::default(),
+            wrap: Wrap::default(),
+            winding_epsilon: 0.0,
+            avoid_punctures: false,
+            defer_word: false,
+            orientation: OrientationConvention::default(),
+            shared_punctures: None,
+            word_history: VecDeque::new(),
+            word_history_len: 0,
+            timestamps: vec![Duration::ZERO],
+        }
+    }
 
-        let puncture_point_inside = PuncturePoint::new(*p1, 'A');
-        let puncture_point_inside_2 = PuncturePoint::new(*p2, 'B');
-        let puncture_point_outside = PuncturePoint::new(*p3, 'A');
+    /// A new path at `start` around the puncture points of a shared `handle`.
+    ///
+    /// The path keeps referencing `handle`: after the puncture points are changed through it,
+    /// [`PathType::sync_punctures`] picks up the new set without copying it.
+    pub fn from_handle(start: Vec2, handle: &PuncturePointsHandle<L>) -> Self {
+        let (version, puncture_points) = handle.snapshot();
+        let mut path_type = Self::new(start, Vec::new());
+        path_type.puncture_points = puncture_points;
+        path_type.shared_punctures = Some((handle.clone(), version));
+        path_type
+    }
 
-        assert!(puncture_point_inside.is_in_triangle(p1, p2, p3));
-        assert!(puncture_point_inside_2.is_in_triangle(p1, p2, p3));
-        assert!(!puncture_point_outside.is_in_triangle(p1, p2, p3));
+    /// Checks if the path was made with [`PathType::from_handle`] and the handle has changed since the last sync.
+    pub fn has_stale_punctures(&self) -> bool {
+        self.shared_punctures
+            .as_ref()
+            .is_some_and(|(handle, version)| handle.version() != *version)
     }
 
-    #[test]
-    fn test_simplify_word_with_multibyte_chars() {
-        let mut word = "ßAa".to_string();
-        simplify_word(&mut word);
-        assert_eq!(word, "ß");
+    /// Takes the current puncture points of the shared handle, if they changed, and recomputes the word.
+    /// Returns whether the puncture points changed.
+    pub fn sync_punctures(&mut self) -> bool {
+        let Some((handle, version)) = &mut self.shared_punctures else {
+            return false;
+        };
+        let (latest, puncture_points) = handle.snapshot();
+        if latest == *version {
+            return false;
+        }
+        *version = latest;
+        self.puncture_points = puncture_points;
+        self.dirty = true;
+        self.update_word();
+        true
+    }
+
+    /// Adds `puncture` to the puncture points and recomputes the word. Returns whether the word changed.
+    ///
+    /// For a path made with [`PathType::from_handle`], add the puncture point through the handle instead,
+    /// since the next change of the handle replaces the puncture points of the path.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DuplicateLabel`] and leaves the path unchanged if a puncture point already has the label of `puncture`,
+    /// as their letters could not be told apart.
+    pub fn insert_puncture(&mut self, puncture: PuncturePoint<L>) -> Result<bool, DuplicateLabel> {
+        if self.has_label(puncture.label()) {
+            return Err(DuplicateLabel);
+        }
+        let puncture_points = self
+            .puncture_points
+            .iter()
+            .cloned()
+            .chain([puncture])
+            .collect();
+        Ok(self.replace_punctures(puncture_points))
+    }
+
+    /// Checks if one of the puncture points has `label`.
+    fn has_label(&self, label: &L) -> bool {
+        self.puncture_points.iter().any(|p| p.label() == label)
+    }
+
+    /// Replaces the puncture points and recomputes the word. Returns whether the word changed.
+    fn replace_punctures(&mut self, puncture_points: Arc<[PuncturePoint<L>]>) -> bool {
+        // A stale word would count the pushed nodes as a change.
+        self.update_word();
+        let previous = self.letters.clone();
+        self.puncture_points = puncture_points;
+        self.dirty = true;
+        self.update_word();
+        self.letters != previous
+    }
+
+    /// The identity loop at `basepoint`: a single node, with an empty word.
+    ///
+    /// This is the same as [`PathType::new`], but names the intent when composing loops with `*` and `-`.
+    pub fn identity(basepoint: Vec2, puncture_points: Vec<PuncturePoint<L>>) -> Self {
+        Self::new(basepoint, puncture_points)
+    }
+
+    pub fn from_path(path: PLPath, puncture_points: Arc<[PuncturePoint<L>]>) -> Self {
+        let mut path_type = Self::unevaluated(path, puncture_points);
+        path_type.update_word();
+        path_type
+    }
+
+    /// A new path type for `path`, with the puncture points and word settings of `self`.
+    ///
+    /// Loops derived from `self`, e.g. its products and inverse, go through this so their words are written
+    /// the same way as the word of `self`.
+    fn derived(&self, path: PLPath) -> Self {
+        let mut path_type = Self::unevaluated(path, self.puncture_points.clone());
+        path_type.openness = self.openness;
+        path_type.wrap = self.wrap;
+        path_type.winding_epsilon = self.winding_epsilon;
+        path_type.avoid_punctures = self.avoid_punctures;
+        path_type.orientation = self.orientation;
+        path_type.update_word();
+        path_type
+    }
+
+    /// A new path type for `path` with the default settings, whose word is yet to be computed.
+    fn unevaluated(path: PLPath, puncture_points: Arc<[PuncturePoint<L>]>) -> Self {
+        let timestamps = vec![Duration::ZERO; path.nodes.len()];
+        Self {
+            current_path: path,
+            puncture_points,
+            letters: Vec::new(),
+            segment_letters: Vec::new(),
+            word: String::new(),
+            dirty: true,
+            crossing_counts: HashMap::new(),
+            openness: Openness::default(),
+            wrap: Wrap::default(),
+            winding_epsilon: 0.0,
+            avoid_punctures: false,
+            defer_word: false,
+            orientation: OrientationConvention::default(),
+            shared_punctures: None,
+            word_history: VecDeque::new(),
+            word_history_len: 0,
+            timestamps,
+        }
+    }
+
+    /// Sets whether the word is computed for the path closed into a loop, or for the open path.
+    #[must_use]
+    pub fn with_openness(mut self, openness: Openness) -> Self {
+        self.openness = openness;
+        self.dirty = true;
+        self.update_word();
+        self
+    }
+
+    /// Keeps a history of the last `len` distinct words, starting from the current word.
+    ///
+    /// A `len` of `0` disables the history.
+    #[must_use]
+    pub fn with_word_history(mut self, len: usize) -> Self {
+        self.word_history_len = len;
+        self.word_history.clear();
+        self.record_word();
+        self
+    }
+
+    /// Returns the most recent distinct words, oldest first.
+    ///
+    /// Empty unless a history length was set with [`PathType::with_word_history`].
+    pub const fn recent_words(&self) -> &VecDeque<String> {
+        &self.word_history
+    }
+
+    /// Appends the current word to the history if it differs from the last one, dropping the oldest words past the cap.
+    fn record_word(&mut self) {
+        if self.word_history_len == 0 || self.word_history.back() == Some(&self.word) {
+            return;
+        }
+        self.word_history.push_back(self.word.clone());
+        while self.word_history.len() > self.word_history_len {
+            self.word_history.pop_front();
+        }
+    }
+
+    /// Returns whether the word is computed for the path closed into a loop, or for the open path.
+    pub const fn openness(&self) -> Openness {
+        self.openness
+    }
+
+    /// Sets the periods of a wrapping world, so steps across a wrap boundary are not treated as long segments.
+    #[must_use]
+    pub fn with_wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self.dirty = true;
+        self.update_word();
+        self
+    }
+
+    /// Returns the periods of the world, if it wraps around.
+    pub const fn wrap(&self) -> Wrap {
+        self.wrap
+    }
+
+    /// Sets which direction around a puncture point is written as the generator.
+    #[must_use]
+    pub fn with_orientation(mut self, orientation: OrientationConvention) -> Self {
+        self.orientation = orientation;
+        self.dirty = true;
+        self.update_word();
+        self
+    }
+
+    /// Returns which direction around a puncture point is written as the generator.
+    pub const fn orientation(&self) -> OrientationConvention {
+        self.orientation
+    }
+
+    /// Writes out `letters`, which count clockwise as `+1`, under the orientation convention.
+    fn render(&self, letters: &[(L, i32)]) -> String {
+        render_letters(&self.oriented(letters))
+    }
+
+    /// Flips the exponents of `letters`, which count clockwise as `+1`, if the orientation convention asks for it.
+    fn oriented(&self, letters: &[(L, i32)]) -> Vec<(L, i32)> {
+        match self.orientation {
+            OrientationConvention::ClockwisePositive => letters.to_vec(),
+            OrientationConvention::CounterclockwisePositive => letters
+                .iter()
+                .map(|(label, exponent)| (label.clone(), -exponent))
+                .collect(),
+        }
+    }
+
+    /// Sets how close a segment may pass to a puncture point before it no longer counts as crossing over it.
+    ///
+    /// Near-grazing segments then add no letter rather than one of either sign, which keeps the word
+    /// from flickering while the path hovers at the level of a puncture point. Defaults to `0.0`.
+    #[must_use]
+    pub fn with_winding_epsilon(mut self, epsilon: f32) -> Self {
+        self.winding_epsilon = epsilon;
+        self.dirty = true;
+        self.update_word();
+        self
+    }
+
+    /// Returns how close a segment may pass to a puncture point while still crossing over it.
+    pub const fn winding_epsilon(&self) -> f32 {
+        self.winding_epsilon
+    }
+
+    /// Sets whether [`PathType::push`] moves points that land inside a puncture point's shape out of it.
+    ///
+    /// Such a point is moved along the direction it was approached from to just past the edge of the shape,
+    /// so a player grazing a hole never records a node on the puncture point itself and the word stays
+    /// well defined. This slightly alters the recorded geometry to keep the homotopy class clean.
+    /// Bare puncture points, with a radius of `0.0`, have no inside. Defaults to `false`.
+    #[must_use]
+    pub const fn with_puncture_avoidance(mut self, avoid_punctures: bool) -> Self {
+        self.avoid_punctures = avoid_punctures;
+        self
+    }
+
+    /// Moves `point` out of the shape of each puncture point it lands inside, along the direction
+    /// from the end of the path, or away from the puncture point if there is no such direction.
+    fn nudge_out_of_punctures(&self, point: Vec2) -> Vec2 {
+        let incoming = self
+            .current_path
+            .nodes
+            .last()
+            .map_or(Vec2::ZERO, |end| (point - *end).normalize_or_zero());
+        self.puncture_points.iter().fold(point, |point, puncture| {
+            let center = *puncture.position();
+            let direction = [incoming, (point - center).normalize_or_zero()]
+                .into_iter()
+                .find(|direction| *direction != Vec2::ZERO)
+                .unwrap_or(Vec2::Y);
+            puncture
+                .shape()
+                .exit_along(center, point, direction)
+                .map_or(point, |exit| exit + direction * PUNCTURE_AVOIDANCE_MARGIN)
+        })
+    }
+
+    /// The segments travelled from `start` to `end`.
+    ///
+    /// A step across a wrap boundary is split into two translated copies of the short step:
+    /// one leaving from `start` past the boundary, and one arriving at `end` from beyond the opposite boundary.
+    /// Every puncture point passed over on the way lies under exactly one of them.
+    fn travelled_segments(&self, start: Vec2, end: Vec2) -> impl Iterator<Item = (Vec2, Vec2)> {
+        let (first, second) = self
+            .wrap
+            .wrapped_step(start, end)
+            .map_or(((start, end), None), |step| {
+                ((start, start + step), Some((end - step, end)))
+            });
+        std::iter::once(first).chain(second)
+    }
+
+    /// Appends `other` to the current path, keeping the puncture points and settings of `self`.
+    ///
+    /// See [`PathType::try_concatenate`] to concatenate two `PathType`s checked against each other.
+    #[must_use]
+    pub fn concatenate(&self, other: &PLPath) -> Self {
+        self.derived(self.current_path.concatenate(other))
+    }
+
+    /// Concatenates the paths of `self` and `other`, if they have the same puncture points.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PunctureMismatch`] if the puncture points differ, as the word of the result would be meaningless.
+    pub fn try_concatenate(&self, other: &Self) -> Result<Self, PunctureMismatch> {
+        if self.has_same_punctures(other) {
+            Ok(self.concatenate(&other.current_path))
+        } else {
+            Err(PunctureMismatch)
+        }
+    }
+
+    /// Enumerates the reduced words of length at most `max_length` over the generators of `puncture_points`,
+    /// one per homotopy class, shortest first.
+    ///
+    /// Words of the same length are in the order of the puncture points, clockwise before counterclockwise.
+    /// The words are freely reduced, so the orders of cone points are ignored and a class of a cone point
+    /// can be listed by several words. The number of words grows exponentially with `max_length`.
+    pub fn enumerate_classes(
+        puncture_points: &[PuncturePoint<L>],
+        max_length: usize,
+    ) -> Vec<String> {
+        let generators: Vec<(L, i32)> = puncture_points
+            .iter()
+            .flat_map(|puncture| {
+                [
+                    (puncture.label().clone(), 1),
+                    (puncture.label().clone(), -1),
+                ]
+            })
+            .collect();
+        let mut classes = vec![String::new()];
+        let mut frontier: Vec<Vec<(L, i32)>> = vec![Vec::new()];
+        for _ in 0..max_length {
+            let mut next = Vec::new();
+            for word in &frontier {
+                for generator in &generators {
+                    if word.last().is_some_and(|(label, exponent)| {
+                        *label == generator.0 && *exponent == -generator.1
+                    }) {
+                        continue;
+                    }
+                    let mut letters = word.clone();
+                    letters.push(generator.clone());
+                    classes.push(render_letters(&letters));
+                    next.push(letters);
+                }
+            }
+            frontier = next;
+        }
+        classes
+    }
+
+    /// A lollipop loop at `basepoint` representing the generator of `puncture`: a straight segment
+    /// to the circle of `radius` around the puncture point, once clockwise around the circle, and back.
+    ///
+    /// `radius` should be small enough that the circle encloses no other puncture point. Puncture points
+    /// passed over by the straight segment conjugate the generator; use [`PLPath::reverse`] for its inverse.
+    pub fn generator_loop(puncture: &PuncturePoint<L>, radius: f32, basepoint: Vec2) -> PLPath {
+        const SIDES: usize = 16;
+        let center = *puncture.position();
+        let direction = (center - basepoint).try_normalize().unwrap_or(Vec2::X);
+        let approach = center - radius * direction;
+        let start_angle = (-direction).to_angle();
+        let circle = (1..SIDES).map(|side| {
+            let angle = start_angle - std::f32::consts::TAU * side as f32 / SIDES as f32;
+            center + radius * Vec2::from_angle(angle)
+        });
+        let mut nodes = vec![basepoint, approach];
+        nodes.extend(circle);
+        nodes.extend([approach, basepoint]);
+        PLPath::new(nodes)
+    }
+
+    /// Transports the loop along `connecting`, which goes from the new basepoint to the current one:
+    /// `connecting`, then the loop, then `connecting` in reverse.
+    ///
+    /// The word of the result is the word of the loop conjugated by the word of `connecting`.
+    #[must_use]
+    pub fn change_basepoint(&self, connecting: &PLPath) -> Self {
+        self.derived(
+            connecting
+                .concatenate(&self.current_path)
+                .concatenate(&connecting.reverse()),
+        )
+    }
+
+    /// Clears the path back to its first node, to start tracing a new loop from the same basepoint.
+    ///
+    /// The puncture points and settings are kept, and so is the word history; the crossing counts are reset.
+    pub fn restart(&mut self) {
+        self.current_path.nodes.truncate(1);
+        self.timestamps.truncate(1);
+        self.crossing_counts.clear();
+        self.dirty = true;
+        self.update_word();
+    }
+
+    fn pop(&mut self) -> Option<Vec2> {
+        self.dirty = true;
+        self.timestamps.pop();
+        self.current_path.nodes.pop()
+    }
+
+    /// Appends a 2d position to the end of the current path.
+    ///
+    /// The node is timestamped with the time of the last node, or [`Duration::ZERO`] for an empty path.
+    pub fn push(&mut self, point: &Vec2) {
+        let time = self.timestamps.last().copied().unwrap_or_default();
+        self.push_at(point, time);
+    }
+
+    /// Appends a 2d position to the end of the current path, but only if it is farther than `epsilon`
+    /// from the end of the path. Returns whether the position was pushed.
+    ///
+    /// An `epsilon` of `0.0` pushes every position that differs from the end of the path.
+    pub fn push_if_moved(&mut self, point: &Vec2, epsilon: f32) -> bool {
+        let moved = self
+            .current_path
+            .nodes
+            .last()
+            .is_none_or(|end| end.distance(*point) > epsilon);
+        if moved {
+            self.push(point);
+        }
+        moved
+    }
+
+    /// Appends a 2d position to the end of the current path, recording `time` as when it was added.
+    ///
+    /// `time` is usually the elapsed time since startup, but any monotonic clock works.
+    pub fn push_at(&mut self, point: &Vec2, time: Duration) {
+        let point = &if self.avoid_punctures {
+            self.nudge_out_of_punctures(*point)
+        } else {
+            *point
+        };
+        if let Some(&end) = self.current_path.nodes.last() {
+            for (start, end) in self.travelled_segments(end, *point) {
+                for puncture in self.puncture_points.iter() {
+                    if puncture
+                        .winding_update(&start, &end, self.winding_epsilon)
+                        .is_none()
+                    {
+                        continue;
+                    }
+                    *self
+                        .crossing_counts
+                        .entry(puncture.label().clone())
+                        .or_default() += 1;
+                }
+            }
+        }
+        self.push_node(point, time);
+        if !self.defer_word {
+            self.update_word();
+        }
+    }
+
+    /// Appends a node, first removing prior nodes that can be pulled tight around the puncture points.
+    ///
+    /// Nodes next to a step across a wrap boundary are kept, since the triangle spanned in the plane
+    /// is not the one travelled.
+    fn push_node(&mut self, point: &Vec2, time: Duration) {
+        if let [.., p1, p2] = &self.current_path.nodes[..] {
+            let wraps = self.wrap.wrapped_step(*p1, *p2).is_some()
+                || self.wrap.wrapped_step(*p2, *point).is_some();
+            if !wraps && should_remove(p1, p2, point, &self.puncture_points) {
+                self.pop();
+                self.push_node(point, time);
+                return;
+            }
+        }
+        self.current_path.push(point);
+        self.timestamps.push(time);
+        self.dirty = true;
+    }
+
+    /// Returns when each node of the current path was pushed, in the same order as the nodes.
+    ///
+    /// Nodes that were not added with [`PathType::push_at`] share the timestamp of the node before them,
+    /// and nodes of a path passed to [`PathType::from_path`] are timestamped [`Duration::ZERO`].
+    pub fn timestamps(&self) -> &[Duration] {
+        &self.timestamps
+    }
+
+    /// Returns how many times pushed segments have passed over each puncture point, by name.
+    ///
+    /// Unlike the word, these counts never cancel out: going over a puncture point and straight
+    /// back counts as two crossings, even though the word is unchanged.
+    pub const fn crossing_counts(&self) -> &HashMap<L, u32> {
+        &self.crossing_counts
+    }
+
+    /// Appends the XY-position of each Transform to the end of the current path.
+    ///
+    /// Each position goes through [`PathType::push`], so nodes are simplified as they are added.
+    #[cfg(feature = "bevy")]
+    pub fn push_transforms(&mut self, transforms: impl IntoIterator<Item = Transform>) {
+        for transform in transforms {
+            self.push(&transform.translation.truncate());
+        }
+    }
+
+    /// Checks if nodes were pushed since the word was last computed, so the stored word is out of date.
+    pub const fn is_word_stale(&self) -> bool {
+        self.dirty
+    }
+
+    /// Updates the word representing the homotopy type of the path.
+    /// Returns the updated word.
+    ///
+    /// The word is only recomputed if the nodes have changed since the last update.
+    pub fn update_word(&mut self) -> String {
+        if !self.dirty {
+            return self.word.clone();
+        }
+        #[cfg(test)]
+        WORD_RECOMPUTATIONS.with(|count| count.set(count.get() + 1));
+        self.segment_letters = self.compute_segment_letters(&self.current_path);
+        self.letters = self.reduce_segment_letters(&self.segment_letters);
+        debug_assert!(
+            letters_are_reduced(&self.letters),
+            "the word of a path should be freely reduced"
+        );
+        self.word = self.render(&self.letters);
+        self.dirty = false;
+        self.record_word();
+        self.word.clone()
+    }
+
+    /// Computes the reduced word of the current path from scratch, without touching the stored word.
+    fn compute_letters(&self) -> Vec<(L, i32)> {
+        self.reduce_segment_letters(&self.compute_segment_letters(&self.current_path))
+    }
+
+    /// Computes the word the path would have had after its first `k` nodes, without changing the path.
+    ///
+    /// The prefix is closed back to its start, unless the path is [`Openness::Open`], so that
+    /// `word_at_prefix(n)` for the number of nodes `n` is the current word. Larger `k` are clamped.
+    /// Since nodes are tightened as they are pushed, the prefixes are those of the tightened path.
+    pub fn word_at_prefix(&self, k: usize) -> String {
+        let nodes = &self.current_path.nodes;
+        let prefix = PLPath::new(&nodes[..k.min(nodes.len())]);
+        let letters = self.reduce_segment_letters(&self.compute_segment_letters(&prefix));
+        self.render(&letters)
+    }
+
+    /// Computes the unreduced letters contributed by each segment of `path`, including the closing segment of a loop.
+    ///
+    /// This gives the same letters as [`PathType::segment_winding`] on every travelled segment, but loops over
+    /// the puncture points on the outside, testing each against contiguous arrays of segment endpoints,
+    /// which keeps long paths with many puncture points cache friendly.
+    fn compute_segment_letters(&self, path: &PLPath) -> Vec<Vec<(L, i32)>> {
+        let capacity = path.nodes.len();
+        let (mut owners, mut starts, mut ends) = (
+            Vec::with_capacity(capacity),
+            Vec::with_capacity(capacity),
+            Vec::with_capacity(capacity),
+        );
+        let mut segment_count = 0;
+        for (index, (start, end)) in path.segments(self.openness).enumerate() {
+            segment_count += 1;
+            for (start, end) in self.travelled_segments(*start, *end) {
+                owners.push(index);
+                starts.push(start);
+                ends.push(end);
+            }
+        }
+        let tolerances: Vec<f32> = starts
+            .iter()
+            .zip(&ends)
+            .map(|(start, end)| self.winding_epsilon * start.distance(*end))
+            .collect();
+        let positions: Vec<Vec2> = self
+            .puncture_points
+            .iter()
+            .map(|puncture| *puncture.position())
+            .collect();
+        let mut segment_letters = vec![Vec::new(); segment_count];
+        // `(piece, along, puncture, n)` for every crossing in the current block, in puncture order.
+        let mut crossings: Vec<(usize, f32, usize, i32)> = Vec::new();
+        for block in (0..starts.len()).step_by(CROSSING_BLOCK) {
+            let pieces = block..(block + CROSSING_BLOCK).min(starts.len());
+            let (block_starts, block_ends, block_tolerances) = (
+                &starts[pieces.clone()],
+                &ends[pieces.clone()],
+                &tolerances[pieces],
+            );
+            crossings.clear();
+            for (puncture, position) in positions.iter().enumerate() {
+                for (offset, ((start, end), tolerance)) in block_starts
+                    .iter()
+                    .zip(block_ends)
+                    .zip(block_tolerances)
+                    .enumerate()
+                {
+                    if let Some(n) = winding_update_within(position, start, end, *tolerance) {
+                        let along = (*position - *start).dot(*end - *start);
+                        crossings.push((block + offset, along, puncture, n));
+                    }
+                }
+            }
+            // The sort is stable, so crossings at the same position along a segment stay in puncture order.
+            crossings.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+            for &(piece, _, puncture, n) in &crossings {
+                segment_letters[owners[piece]]
+                    .push((self.puncture_points[puncture].label().clone(), n));
+            }
+        }
+        segment_letters
+    }
+
+    /// Concatenates the letters of every segment and reduces them into the word.
+    fn reduce_segment_letters(&self, segment_letters: &[Vec<(L, i32)>]) -> Vec<(L, i32)> {
+        let mut letters: Vec<(L, i32)> = segment_letters.concat();
+        let orders: HashMap<&L, u32> = self
+            .puncture_points
+            .iter()
+            .filter_map(|puncture| puncture.order().map(|order| (puncture.label(), order)))
+            .collect();
+        reduce_letters(&mut letters, |label| orders.get(label).copied());
+        letters
+    }
+
+    /// Returns the puncture points passed over by the line segment from `start` to `end`,
+    /// in the order they contribute to the word, with the direction of each crossing.
+    ///
+    /// `1` means the segment passes left -> right above the puncture point (a lowercase letter),
+    /// and `-1` means it passes right -> left (an uppercase letter).
+    ///
+    /// When several puncture points lie under the segment, they are emitted in the order the segment
+    /// sweeps across them, so traversing the segment backwards gives the inverse word.
+    /// Puncture points at the same position along the segment keep their array order.
+    pub fn segment_winding(&self, start: Vec2, end: Vec2) -> Vec<(L, i32)> {
+        if start.x.partial_cmp(&end.x).is_none_or(Ordering::is_eq) {
+            return Vec::new();
+        }
+        let direction = end - start;
+        let mut crossings: Vec<(f32, L, i32)> = self
+            .puncture_points
+            .iter()
+            .filter_map(|puncture| {
+                puncture
+                    .winding_update(&start, &end, self.winding_epsilon)
+                    .map(|n| {
+                        let along = (*puncture.position() - start).dot(direction);
+                        (along, puncture.label().clone(), n)
+                    })
+            })
+            .collect();
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        crossings
+            .into_iter()
+            .map(|(_, name, n)| (name, n))
+            .collect()
+    }
+
+    /// Checks that the stored word matches the word recomputed from the path and puncture points.
+    ///
+    /// Useful after loading a `PathType` from untrusted or hand-edited data.
+    pub fn validate(&self) -> bool {
+        self.letters == self.compute_letters()
+    }
+
+    /// Recomputes the stored word from the path and puncture points, discarding whatever was there.
+    pub fn repair(&mut self) {
+        self.dirty = true;
+        self.update_word();
+    }
+
+    /// Approximates the length of the shortest loop in the homotopy class of the path,
+    /// treating each puncture point as a disk of radius `puncture_radius`.
+    ///
+    /// This is an approximation: starting from the basepoint, the loop is assumed to travel in a straight line
+    /// to the edge of the disk of each letter of the word in turn, go once around it, and finally
+    /// return to the basepoint. Neither the straight legs nor the full turns are guaranteed to be
+    /// the true taut representative, but the result is a useful "par" length.
+    pub fn taut_length(&self, puncture_radius: f32) -> f32 {
+        let basepoint = *self.current_path.start();
+        let stops: Vec<Vec2> = self
+            .letters
+            .iter()
+            .filter_map(|(label, _)| {
+                self.puncture_points
+                    .iter()
+                    .find(|puncture| puncture.label() == label)
+                    .map(|puncture| *puncture.position())
+            })
+            .collect();
+        if stops.is_empty() {
+            return 0.0;
+        }
+        let turns = stops.len() as f32 * std::f32::consts::TAU * puncture_radius;
+        let first_leg = basepoint.distance(stops[0]) - puncture_radius;
+        let last_leg = basepoint.distance(stops[stops.len() - 1]) - puncture_radius;
+        let middle_legs: f32 = stops
+            .windows(2)
+            .map(|pair| {
+                puncture_radius
+                    .mul_add(-2.0, pair[0].distance(pair[1]))
+                    .max(0.0)
+            })
+            .sum();
+        turns + first_leg.max(0.0) + middle_legs + last_leg.max(0.0)
+    }
+
+    /// Splits the path at each return to within `threshold` of its start, returning the word of each sub-loop in order.
+    ///
+    /// Each sub-loop runs from one return, or the start, to the next, and is closed back to where it began.
+    /// Whatever follows the last return is the final sub-loop. A return only splits the path once it has
+    /// moved on from the previous one, so lingering near the start does not produce extra sub-loops.
+    /// As long as no branch cut passes within `threshold` of the start, the product of the words, e.g. by
+    /// [`reduce_concat`](crate::word::reduce_concat), is the word of the whole loop.
+    ///
+    /// Nodes pulled tight since they were pushed are gone, so returns are only found among the current nodes.
+    pub fn decompose_loops(&self, threshold: f32) -> Vec<String> {
+        let nodes = &self.current_path.nodes;
+        let Some(&start) = nodes.first() else {
+            return Vec::new();
+        };
+        let mut splits = vec![0];
+        for (index, node) in nodes.iter().enumerate().skip(1) {
+            let previous = splits[splits.len() - 1];
+            if index > previous + 1 && node.distance(start) <= threshold {
+                splits.push(index);
+            }
+        }
+        if splits[splits.len() - 1] + 1 < nodes.len() {
+            splits.push(nodes.len() - 1);
+        }
+        splits
+            .windows(2)
+            .map(|pair| {
+                let sub_loop = PLPath::new(&nodes[pair[0]..=pair[1]]);
+                let letters = self.reduce_segment_letters(&self.compute_segment_letters(&sub_loop));
+                self.render(&letters)
+            })
+            .collect()
+    }
+
+    /// Returns the length of the traced path, with each segment weighted by how close it comes to a puncture point.
+    ///
+    /// Each segment counts its length times `exp(-d / falloff)`, where `d` is the distance from the segment
+    /// to the nearest puncture point, so segments grazing a puncture point count fully and distant ones
+    /// hardly at all. Only the traced segments count, not the segment closing a loop. `falloff` should be positive.
+    pub fn hazard_weighted_length(&self, falloff: f32) -> f32 {
+        self.current_path
+            .segments(Openness::Open)
+            .map(|(start, end)| {
+                let distance = self
+                    .puncture_points
+                    .iter()
+                    .map(|puncture| segment_distance(*puncture.position(), *start, *end))
+                    .fold(f32::INFINITY, f32::min);
+                start.distance(*end) * (-distance / falloff).exp()
+            })
+            .sum()
+    }
+
+    /// Checks if the word of `other` appears as a contiguous piece of the word of `self`.
+    ///
+    /// This is a syntactic check on the reduced words, not a geometric one: it does not mean
+    /// that `other` can be traced inside `self`. Paths with different puncture points never contain each other.
+    pub fn contains_subloop(&self, other: &Self) -> bool {
+        self.has_same_punctures(other)
+            && (other.letters.is_empty()
+                || self
+                    .letters
+                    .windows(other.letters.len())
+                    .any(|window| window == other.letters))
+    }
+
+    /// Returns the L1 distance between the winding numbers of the two loops,
+    /// i.e. the sum over all puncture points of the absolute difference in winding number.
+    ///
+    /// This is a cheap measure of how differently two loops wind, which ignores the order
+    /// in which they go around the puncture points.
+    ///
+    /// ## Panics
+    /// This will panic if the two paths don't have the same puncture points.
+    pub fn abelian_distance(&self, other: &Self) -> i32 {
+        assert!(
+            self.has_same_punctures(other),
+            "Couldn't compare paths with different puncture points"
+        );
+        let theirs: HashMap<&L, i32> = other
+            .puncture_points
+            .iter()
+            .map(PuncturePoint::label)
+            .zip(other.winding_vector())
+            .collect();
+        self.puncture_points
+            .iter()
+            .zip(self.winding_vector())
+            .map(|(puncture, winding)| (winding - theirs[puncture.label()]).abs())
+            .sum()
+    }
+
+    /// Checks if both paths have the same puncture points, regardless of their order.
+    fn has_same_punctures(&self, other: &Self) -> bool {
+        let count = |puncture_points: &[PuncturePoint<L>], puncture: &PuncturePoint<L>| {
+            puncture_points.iter().filter(|p| *p == puncture).count()
+        };
+        self.puncture_points.len() == other.puncture_points.len()
+            && self.puncture_points.iter().all(|puncture| {
+                count(&self.puncture_points, puncture) == count(&other.puncture_points, puncture)
+            })
+    }
+
+    /// Returns the signed winding number of the loop around each puncture point,
+    /// in the same order as the puncture points.
+    ///
+    /// Clockwise traversal counts as `+1`, matching the lowercase generators of the word,
+    /// so this is the image of the word in `Z^n`.
+    pub fn winding_vector(&self) -> Vec<i32> {
+        self.puncture_points
+            .iter()
+            .map(|puncture| self.current_path.winding_number(puncture.position()))
+            .collect()
+    }
+
+    /// Returns the names of the puncture points appearing in the reduced word: the support of the homotopy class.
+    ///
+    /// Unlike [`PathType::enclosed_punctures`], a puncture point the path winds around and back out of
+    /// is in the support if the word still has to go around it, e.g. the `a` and `A` of `abAB`.
+    pub fn support(&self) -> HashSet<L> {
+        self.letters
+            .iter()
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+
+    /// Returns `(reduced_length, distinct_generators)`: the length of the reduced word and the size of its
+    /// [support](PathType::support), a two-axis measure of how hard the homotopy class is to reach.
+    pub fn complexity(&self) -> (usize, usize) {
+        let reduced_length = self
+            .letters
+            .iter()
+            .map(|(_, exponent)| exponent.unsigned_abs() as usize)
+            .sum();
+        (reduced_length, self.support().len())
+    }
+
+    /// Returns the names of the puncture points enclosed by the loop under the given fill rule.
+    ///
+    /// With [`FillRule::NonZero`], these are the puncture points with a nonzero winding number.
+    pub fn enclosed_punctures(&self, fill_rule: FillRule) -> Vec<L> {
+        self.puncture_points
+            .iter()
+            .filter(|puncture| {
+                self.current_path
+                    .contains_point(puncture.position(), fill_rule)
+            })
+            .map(|puncture| puncture.label().clone())
+            .collect()
+    }
+
+    /// Returns the puncture points that matter for the path under `relevance`, in their original order,
+    /// e.g. to leave the others out of a diagram.
+    ///
+    /// Only [`Relevance::Word`] guarantees that the word is unchanged without the other puncture points:
+    /// a loop can have a nontrivial word around puncture points it does not wind around, like `abAB`.
+    pub fn relevant_punctures(&self, relevance: Relevance) -> Vec<PuncturePoint<L>> {
+        let nodes = &self.current_path.nodes;
+        let (min, max) = nodes.iter().fold(
+            (Vec2::splat(f32::INFINITY), Vec2::splat(f32::NEG_INFINITY)),
+            |(min, max), node| (min.min(*node), max.max(*node)),
+        );
+        let support = self.support();
+        self.puncture_points
+            .iter()
+            .filter(|puncture| match relevance {
+                Relevance::Winding => self.current_path.winding_number(puncture.position()) != 0,
+                Relevance::BoundingBox => {
+                    let position = *puncture.position();
+                    position.cmpge(min).all() && position.cmple(max).all()
+                }
+                Relevance::Word => support.contains(puncture.label()),
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the signed area enclosed by the loop, by the shoelace formula.
+    ///
+    /// The area is positive for counterclockwise loops, as usual for the shoelace formula,
+    /// which is the opposite sign of the winding numbers. Regions wound around several times count
+    /// as many times, and self-intersecting loops can cancel out.
+    pub fn enclosed_area(&self) -> f32 {
+        self.current_path.signed_area()
+    }
+
+    /// Checks if the loop separates the puncture points named `a` and `b`,
+    /// i.e. if it winds around exactly one of them.
+    ///
+    /// Returns `None` if either name is not one of the puncture points.
+    pub fn separates(&self, a: &L, b: &L) -> Option<bool> {
+        let winds_around = |label: &L| {
+            self.puncture_points
+                .iter()
+                .find(|puncture| puncture.label() == label)
+                .map(|puncture| self.current_path.winding_number(puncture.position()) != 0)
+        };
+        Some(winds_around(a)? != winds_around(b)?)
+    }
+
+    /// Reports puncture point configurations that can make the word unreliable.
+    ///
+    /// Crossings are counted along a ray pointing straight up from each puncture point, so
+    /// puncture points sharing an x-coordinate are crossed at the same instant by a segment ending
+    /// at that x-coordinate, and puncture points lying on the path are neither above nor below it.
+    pub fn diagnose(&self) -> Vec<Diagnostic<L>> {
+        let mut diagnostics = Vec::new();
+        for (i, a) in self.puncture_points.iter().enumerate() {
+            for b in &self.puncture_points[i + 1..] {
+                if a.position() == b.position() {
+                    diagnostics.push(Diagnostic::CoincidentPunctures(
+                        a.label().clone(),
+                        b.label().clone(),
+                    ));
+                } else if a.position().x == b.position().x {
+                    diagnostics.push(Diagnostic::SharedX(a.label().clone(), b.label().clone()));
+                }
+            }
+        }
+        for puncture in self.puncture_points.iter() {
+            if self
+                .current_path
+                .segments(self.openness)
+                .any(|(start, end)| point_on_segment(*puncture.position(), *start, *end))
+            {
+                diagnostics.push(Diagnostic::PunctureOnPath(puncture.label().clone()));
+            }
+        }
+        let self_intersections = self.self_intersection_count();
+        if self_intersections > 0 {
+            diagnostics.push(Diagnostic::SelfIntersecting(self_intersections));
+        }
+        diagnostics
+    }
+
+    /// Returns how many times the path crosses itself, see [`PLPath::self_intersections`].
+    pub fn self_intersection_count(&self) -> usize {
+        self.current_path.self_intersections(self.openness).len()
+    }
+
+    /// Captures an owned copy of the path, puncture points, word settings and word.
+    ///
+    /// The snapshot shares nothing with `self`, so it can be sent to another thread.
+    pub fn snapshot(&self) -> PathSnapshot<L> {
+        PathSnapshot {
+            nodes: self.current_path.nodes.clone(),
+            punctures: self.puncture_points.to_vec(),
+            word: self.word(),
+            openness: self.openness,
+            wrap: self.wrap,
+            winding_epsilon: self.winding_epsilon,
+            avoid_punctures: self.avoid_punctures,
+            orientation: self.orientation,
+        }
+    }
+
+    /// Returns how much the loop has wound around each puncture point, by name, since `since` was taken:
+    /// the current [winding vector](PathType::winding_vector) minus that of the snapshot's nodes.
+    ///
+    /// A quick wind and unwind nets out to zero, so sample this between snapshots to see the peak.
+    pub fn winding_delta(&self, since: &PathSnapshot<L>) -> HashMap<L, i32> {
+        let before = PLPath::new(since.nodes.clone());
+        self.puncture_points
+            .iter()
+            .zip(self.winding_vector())
+            .map(|(puncture, winding)| {
+                (
+                    puncture.label().clone(),
+                    winding - before.winding_number(puncture.position()),
+                )
+            })
+            .collect()
+    }
+}
+
+impl PathType {
+    /// Builds a path type from its nodes and `(position, name)` pairs for the puncture points,
+    /// computing the word.
+    ///
+    /// Names are made uppercase, as in [`PuncturePoint::new`].
+    ///
+    /// ## Panics
+    /// This will panic if two puncture points have the same name.
+    pub fn from_parts(nodes: Vec<Vec2>, punctures: Vec<(Vec2, char)>) -> Self {
+        let puncture_points: Vec<PuncturePoint> = punctures
+            .into_iter()
+            .map(|(position, name)| PuncturePoint::new(position, name))
+            .collect();
+        let mut names = HashSet::new();
+        for puncture in &puncture_points {
+            assert!(
+                names.insert(puncture.name()),
+                "Duplicate puncture name {}",
+                puncture.name()
+            );
+        }
+        Self::from_path(PLPath::new(nodes), puncture_points.into())
+    }
+
+    /// Writes the word in a canonical form for other tools, listing simultaneous crossings in the order of `order`.
+    ///
+    /// When one segment passes over several puncture points at the same position along it, such as
+    /// puncture points stacked vertically under a horizontal segment, their letters could be listed
+    /// either way. [`PathType::word`] lists them in the order of the puncture points; here they are
+    /// listed in the order their names appear in `order`, compared ignoring case, followed by any names
+    /// missing from `order` in the order of the puncture points.
+    ///
+    /// If `inverse_as_caret` is set, every letter is written lowercase, with inverses marked `^-1`,
+    /// and letters are separated by spaces, e.g. `a b^-1`. Otherwise the word is written as usual, e.g. `aB`.
+    pub fn word_with_order(&self, order: &[char], inverse_as_caret: bool) -> String {
+        let rank = |name: char| {
+            order
+                .iter()
+                .position(|ordered| {
+                    letter_with_case(*ordered, true) == letter_with_case(name, true)
+                })
+                .unwrap_or(order.len())
+        };
+        let mut puncture_points = self.puncture_points.to_vec();
+        puncture_points.sort_by_key(|puncture| rank(puncture.name()));
+        let mut reordered = self.clone();
+        reordered.puncture_points = puncture_points.into();
+        let letters = self.oriented(&reordered.compute_letters());
+        if !inverse_as_caret {
+            return render_letters(&letters);
+        }
+        letters
+            .iter()
+            .map(|(name, exponent)| {
+                let letter = letter_with_case(*name, false);
+                if *exponent < 0 {
+                    format!("{letter}^-1")
+                } else {
+                    letter.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A suspicious configuration reported by [`PathType::diagnose`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Diagnostic<L = char> {
+    /// Two puncture points, by name, are at the same position.
+    CoincidentPunctures(L, L),
+    /// Two puncture points, by name, have the same x-coordinate, so their crossings can be miscounted
+    /// by segments ending at that x-coordinate.
+    SharedX(L, L),
+    /// The puncture point with this name lies on the path.
+    PunctureOnPath(L),
+    /// The path crosses itself this many times. Its word is still well-defined,
+    /// but is often not the one expected from a loop drawn as a simple outline.
+    SelfIntersecting(usize),
+}
+
+/// The error returned by [`PathType::try_concatenate`] when the paths have different puncture points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PunctureMismatch;
+
+impl std::fmt::Display for PunctureMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("paths have different puncture points")
+    }
+}
+
+impl std::error::Error for PunctureMismatch {}
+
+/// The error returned by [`PathType::insert_puncture`] when a puncture point already has the label being inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DuplicateLabel;
+
+impl std::fmt::Display for DuplicateLabel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("a puncture point already has this label")
+    }
+}
+
+impl std::error::Error for DuplicateLabel {}
+
+/// A set of puncture points shared by many [`PathType`]s, see [`PathType::from_handle`].
+///
+/// Every change allocates the new set once, and bumps a version; paths then share it by reference
+/// when they sync. Clones of a handle refer to the same set.
+#[derive(Debug)]
+pub struct PuncturePointsHandle<L = char> {
+    shared: Arc<RwLock<VersionedPunctures<L>>>,
+}
+
+/// The puncture points of a [`PuncturePointsHandle`] with their version.
+type VersionedPunctures<L> = (u64, Arc<[PuncturePoint<L>]>);
+
+impl<L> Clone for PuncturePointsHandle<L> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<L> PuncturePointsHandle<L> {
+    /// A new shared set of puncture points, at version `0`.
+    pub fn new(puncture_points: Vec<PuncturePoint<L>>) -> Self {
+        Self {
+            shared: Arc::new(RwLock::new((0, puncture_points.into()))),
+        }
+    }
+
+    /// Returns the current version, which increases with every change.
+    pub fn version(&self) -> u64 {
+        self.shared.read().unwrap_or_else(PoisonError::into_inner).0
+    }
+
+    /// Returns the current version together with the current puncture points.
+    pub fn snapshot(&self) -> VersionedPunctures<L> {
+        let shared = self.shared.read().unwrap_or_else(PoisonError::into_inner);
+        (shared.0, Arc::clone(&shared.1))
+    }
+
+    /// Replaces the puncture points.
+    pub fn set(&self, puncture_points: Vec<PuncturePoint<L>>) {
+        let mut shared = self.shared.write().unwrap_or_else(PoisonError::into_inner);
+        *shared = (shared.0 + 1, puncture_points.into());
+    }
+
+    /// Changes the puncture points with `f`, e.g. to add or remove one.
+    pub fn update(&self, f: impl FnOnce(&mut Vec<PuncturePoint<L>>))
+    where
+        L: Clone,
+    {
+        let mut shared = self.shared.write().unwrap_or_else(PoisonError::into_inner);
+        let mut puncture_points = shared.1.to_vec();
+        f(&mut puncture_points);
+        *shared = (shared.0 + 1, puncture_points.into());
+    }
+}
+
+/// Adds `puncture` to each of `path_types` like [`PathType::insert_puncture`], e.g. when a level editor drops
+/// a new hole, and returns the keys of the paths whose word changed, in order.
+///
+/// Paths sharing their puncture points keep sharing them: the new puncture points are allocated once
+/// for every distinct set of puncture points.
+///
+/// The keys can be anything identifying the paths, such as the entities of a query:
+/// `insert_puncture_into(&puncture, query.iter_mut().map(|(entity, path_type)| (entity, path_type.into_inner())))`.
+///
+/// # Errors
+///
+/// Returns [`DuplicateLabel`] and leaves every path unchanged if one of them already has a puncture point
+/// with the label of `puncture`.
+pub fn insert_puncture_into<'a, K, L: Label + 'a>(
+    puncture: &PuncturePoint<L>,
+    path_types: impl IntoIterator<Item = (K, &'a mut PathType<L>)>,
+) -> Result<Vec<K>, DuplicateLabel> {
+    let path_types: Vec<(K, &mut PathType<L>)> = path_types.into_iter().collect();
+    if path_types
+        .iter()
+        .any(|(_, path_type)| path_type.has_label(puncture.label()))
+    {
+        return Err(DuplicateLabel);
+    }
+    // The puncture points before and after the insertion, for each distinct set of puncture points.
+    let mut inserted: Vec<[Arc<[PuncturePoint<L>]>; 2]> = Vec::new();
+    Ok(path_types
+        .into_iter()
+        .filter_map(|(key, path_type)| {
+            let puncture_points = match inserted
+                .iter()
+                .find(|[before, _]| Arc::ptr_eq(before, &path_type.puncture_points))
+            {
+                Some([_, after]) => Arc::clone(after),
+                None => {
+                    let after: Arc<[_]> = path_type
+                        .puncture_points
+                        .iter()
+                        .cloned()
+                        .chain([puncture.clone()])
+                        .collect();
+                    inserted.push([Arc::clone(&path_type.puncture_points), Arc::clone(&after)]);
+                    after
+                }
+            };
+            path_type.replace_punctures(puncture_points).then_some(key)
+        })
+        .collect())
+}
+
+/// An owned copy of the state of a [`PathType`], decoupled from the ECS.
+///
+/// Besides the nodes, puncture points and word, the settings the word depends on are copied,
+/// see the matching `with_*` methods of [`PathType`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathSnapshot<L = char> {
+    pub nodes: Vec<Vec2>,
+    pub punctures: Vec<PuncturePoint<L>>,
+    pub word: String,
+    pub openness: Openness,
+    pub wrap: Wrap,
+    pub winding_epsilon: f32,
+    pub avoid_punctures: bool,
+    pub orientation: OrientationConvention,
+}
+
+impl<L: Label> PathSnapshot<L> {
+    /// Computes the word of the snapshotted nodes around the snapshotted puncture points from scratch,
+    /// with the snapshotted settings, as [`PathType::update_word`] does.
+    pub fn compute_word(&self) -> String {
+        let mut path_type = PathType::unevaluated(
+            PLPath::new(self.nodes.clone()),
+            self.punctures.clone().into(),
+        );
+        path_type.openness = self.openness;
+        path_type.wrap = self.wrap;
+        path_type.winding_epsilon = self.winding_epsilon;
+        path_type.avoid_punctures = self.avoid_punctures;
+        path_type.orientation = self.orientation;
+        path_type.update_word()
+    }
+}
+
+/// Computes the words of `snapshots` in parallel on Bevy's `AsyncComputeTaskPool`, see [`PathSnapshot::compute_word`].
+///
+/// Each snapshot gets its own task, and the returned task resolves to their words in the order of `snapshots`.
+/// The task pool is set up with the default settings if Bevy's `TaskPoolPlugin` has not set it up already,
+/// so this also works outside of an `App`, e.g. for tooling analysing saved paths.
+#[cfg(feature = "bevy")]
+pub fn compute_words_async<L>(snapshots: Vec<PathSnapshot<L>>) -> Task<Vec<String>>
+where
+    L: Label + Send + Sync + 'static,
+{
+    let pool = AsyncComputeTaskPool::get_or_init(TaskPool::default);
+    let tasks: Vec<Task<String>> = snapshots
+        .into_iter()
+        .map(|snapshot| pool.spawn(async move { snapshot.compute_word() }))
+        .collect();
+    pool.spawn(async move {
+        let mut words = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            words.push(task.await);
+        }
+        words
+    })
+}
+
+/// A snapshot of everything needed to reproduce how the word of a path evolved:
+/// the path, its puncture points, and every distinct word it has had, oldest first.
+#[cfg(feature = "serialize")]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HomotopySession {
+    pub path: PLPath,
+    pub puncture_points: Vec<PuncturePoint>,
+    pub word_history: Vec<String>,
+}
+
+#[cfg(feature = "serialize")]
+impl HomotopySession {
+    /// Starts a session from the current state of a path.
+    pub fn new(path_type: &PathType) -> Self {
+        Self {
+            path: path_type.current_path.clone(),
+            puncture_points: path_type.puncture_points.to_vec(),
+            word_history: vec![path_type.word()],
+        }
+    }
+
+    /// Rebuilds the `PathType` at the end of the session.
+    pub fn to_path_type(&self) -> PathType {
+        PathType::from_path(self.path.clone(), self.puncture_points.clone().into())
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl PathType {
+    /// Records the current state of the path into `session`.
+    ///
+    /// Call this whenever the word changes; the word is only appended to the history
+    /// if it differs from the last recorded one.
+    pub fn record_into(&self, session: &mut HomotopySession) {
+        session.path = self.current_path.clone();
+        session.puncture_points = self.puncture_points.to_vec();
+        if session.word_history.last() != Some(&self.word) {
+            session.word_history.push(self.word());
+        }
+    }
+}
+
+/// The serialized form of a [`PathType`]: everything but the word, which is recomputed from the path.
+#[cfg(feature = "serialize")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PathTypeData<L> {
+    path: PLPath,
+    puncture_points: Vec<PuncturePoint<L>>,
+    crossing_counts: Vec<(L, u32)>,
+    #[serde(default)]
+    openness: Openness,
+    #[serde(default)]
+    wrap: Wrap,
+    #[serde(default)]
+    winding_epsilon: f32,
+    #[serde(default)]
+    avoid_punctures: bool,
+    #[serde(default)]
+    orientation: OrientationConvention,
+    #[serde(default)]
+    word_history: VecDeque<String>,
+    #[serde(default)]
+    word_history_len: usize,
+    #[serde(default)]
+    timestamps: Vec<Duration>,
+}
+
+#[cfg(feature = "serialize")]
+impl<L: Label> From<PathType<L>> for PathTypeData<L> {
+    fn from(path_type: PathType<L>) -> Self {
+        Self {
+            path: path_type.current_path,
+            puncture_points: path_type.puncture_points.to_vec(),
+            crossing_counts: path_type.crossing_counts.into_iter().collect(),
+            openness: path_type.openness,
+            wrap: path_type.wrap,
+            winding_epsilon: path_type.winding_epsilon,
+            avoid_punctures: path_type.avoid_punctures,
+            orientation: path_type.orientation,
+            word_history: path_type.word_history,
+            word_history_len: path_type.word_history_len,
+            timestamps: path_type.timestamps,
+        }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<L: Label> From<PathTypeData<L>> for PathType<L> {
+    fn from(data: PathTypeData<L>) -> Self {
+        // Data saved before timestamps were recorded has none, so every node gets `Duration::ZERO`.
+        let mut timestamps = data.timestamps;
+        timestamps.resize(data.path.nodes.len(), Duration::ZERO);
+        let mut path_type = Self {
+            current_path: data.path,
+            puncture_points: data.puncture_points.into(),
+            letters: Vec::new(),
+            segment_letters: Vec::new(),
+            word: String::new(),
+            dirty: true,
+            crossing_counts: data.crossing_counts.into_iter().collect(),
+            openness: data.openness,
+            wrap: data.wrap,
+            winding_epsilon: data.winding_epsilon,
+            avoid_punctures: data.avoid_punctures,
+            defer_word: false,
+            orientation: data.orientation,
+            shared_punctures: None,
+            word_history: data.word_history,
+            word_history_len: data.word_history_len,
+            timestamps,
+        };
+        path_type.update_word();
+        path_type
+    }
+}
+
+/// A change of the word of a [`PathStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordChange {
+    pub previous: String,
+    pub word: String,
+}
+
+/// Feeds a stream of positions, such as replicated remote movement, into a [`PathType`].
+///
+/// Unlike sampling a `Transform` every tick, positions are pushed as they arrive,
+/// and only changes of the word are reported, which suits delta replication.
+#[derive(Debug, Clone)]
+pub struct PathStream<L = char> {
+    path_type: PathType<L>,
+    epsilon: f32,
+}
+
+impl<L: Label> PathStream<L> {
+    /// Streams into `path_type`, ignoring positions within `epsilon` of the end of the path.
+    pub const fn new(path_type: PathType<L>, epsilon: f32) -> Self {
+        Self { path_type, epsilon }
+    }
+
+    /// Pushes `point` with [`PathType::push_if_moved`], returning the change of word if it changed.
+    ///
+    /// The word is brought up to date on every feed, even if the path type defers it.
+    pub fn feed(&mut self, point: Vec2) -> Option<WordChange> {
+        let previous = self.path_type.update_word();
+        if !self.path_type.push_if_moved(&point, self.epsilon) {
+            return None;
+        }
+        let word = self.path_type.update_word();
+        (word != previous).then_some(WordChange { previous, word })
+    }
+
+    /// Returns the path type being streamed into.
+    pub const fn path_type(&self) -> &PathType<L> {
+        &self.path_type
+    }
+
+    /// Stops streaming, returning the path type.
+    pub fn into_inner(self) -> PathType<L> {
+        self.path_type
+    }
+}
+
+/// A [`PathType`] whose nodes each carry a piece of data, such as a tag for an event that happened there.
+///
+/// The data is kept aligned with the nodes: when pushing a node pulls earlier nodes tight,
+/// their data is dropped along with them.
+#[derive(Debug, Clone)]
+pub struct PathTypeWith<T, L = char> {
+    path_type: PathType<L>,
+    node_data: Vec<T>,
+}
+
+impl<T, L: Label> PathTypeWith<T, L> {
+    /// Starts a path at `start`, carrying `start_data`, as in [`PathType::new`].
+    pub fn new(start: Vec2, start_data: T, puncture_points: Vec<PuncturePoint<L>>) -> Self {
+        Self {
+            path_type: PathType::new(start, puncture_points),
+            node_data: vec![start_data],
+        }
+    }
+
+    /// Appends `point`, carrying `data`, to the end of the path with [`PathType::push`].
+    pub fn push(&mut self, point: &Vec2, data: T) {
+        self.path_type.push(point);
+        // Tightening only removes nodes from the end, so the data of the other nodes is still in place.
+        self.node_data
+            .truncate(self.path_type.current_path.nodes.len() - 1);
+        self.node_data.push(data);
+    }
+
+    /// Returns the data of each node, in the same order as the nodes.
+    pub fn node_data(&self) -> &[T] {
+        &self.node_data
+    }
+
+    /// Returns each node with its data.
+    pub fn nodes_with_data(&self) -> impl Iterator<Item = (&Vec2, &T)> + '_ {
+        self.path_type
+            .current_path
+            .nodes
+            .iter()
+            .zip(&self.node_data)
+    }
+
+    /// Returns the path type carrying the data.
+    pub const fn path_type(&self) -> &PathType<L> {
+        &self.path_type
+    }
+
+    /// Drops the data, returning the path type.
+    pub fn into_inner(self) -> PathType<L> {
+        self.path_type
+    }
+}
+
+/// Lazily yields the reduced word after pushing each of `positions`, in turn, onto a path
+/// starting at `start` among `punctures`.
+///
+/// This is the offline counterpart of tracking an entity with [`PathType::push`],
+/// e.g. for analysing a recorded movement stream.
+pub fn words_along<L: Label>(
+    positions: impl IntoIterator<Item = Vec2>,
+    start: Vec2,
+    punctures: Vec<PuncturePoint<L>>,
+) -> impl Iterator<Item = String> {
+    let mut path_type = PathType::new(start, punctures);
+    positions.into_iter().map(move |position| {
+        path_type.push(&position);
+        path_type.word()
+    })
+}
+
+/// Composes two loops based at the same point: `a * b` traverses `a`, then `b`.
+impl<L: Label> Mul for &PathType<L> {
+    type Output = PathType<L>;
+
+    fn mul(self, rhs: Self) -> PathType<L> {
+        debug_assert!(
+            self.has_same_punctures(rhs),
+            "concatenated paths have different puncture points"
+        );
+        self.concatenate(&rhs.current_path)
+    }
+}
+
+impl<L: Label> Mul for PathType<L> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        &self * &rhs
+    }
+}
+
+/// The inverse loop: the same basepoint, with the rest of the nodes traversed in reverse.
+impl<L: Label> Neg for &PathType<L> {
+    type Output = PathType<L>;
+
+    fn neg(self) -> PathType<L> {
+        let mut nodes = self.current_path.nodes.clone();
+        if let Some(rest) = nodes.get_mut(1..) {
+            rest.reverse();
+        }
+        self.derived(PLPath::new(nodes))
+    }
+}
+
+impl<L: Label> Neg for PathType<L> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        -&self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The letters of a `char` word, e.g. `aB` is `[('A', 1), ('B', -1)]`.
+    fn letters(word: &str) -> Vec<(char, i32)> {
+        word.chars()
+            .map(|c| {
+                (
+                    c.to_ascii_uppercase(),
+                    if c.is_ascii_lowercase() { 1 } else { -1 },
+                )
+            })
+            .collect()
+    }
+
+    /// A loop with the `char` word `word`, traced as a product of [generator loops](PathType::generator_loop)
+    /// based below and to the left of `puncture_points`, which should lie on the x-axis at least a unit apart.
+    fn loop_with_word(word: &str, puncture_points: &[PuncturePoint]) -> PathType {
+        let basepoint = Vec2::new(-10.0, -1.0);
+        let path =
+            letters(word)
+                .into_iter()
+                .fold(PLPath::new([basepoint]), |path, (label, exponent)| {
+                    let puncture = puncture_points
+                        .iter()
+                        .find(|puncture| *puncture.label() == label)
+                        .expect("the word only uses the puncture points");
+                    let generator = PathType::generator_loop(puncture, 0.5, basepoint);
+                    path.concatenate(&if exponent > 0 {
+                        generator
+                    } else {
+                        generator.reverse()
+                    })
+                });
+        PathType::from_path(path, puncture_points.to_vec().into())
+    }
+
+    #[test]
+    fn test_is_point_in_triangle() {
+        let p1 = &Vec2::new(0.0, 0.0);
+        let p2 = &Vec2::new(4.0, 0.0);
+        let p3 = &Vec2::new(2.0, 4.0);
+        let inside = |position: Vec2| PuncturePoint::new(position, 'A').is_in_triangle(p1, p2, p3);
+
+        assert!(inside(Vec2::new(2.0, 1.0)));
+        // Every vertex and every edge is part of the triangle.
+        for (start, end) in [(p1, p2), (p2, p3), (p3, p1)] {
+            assert!(inside(*start));
+            assert!(inside(start.lerp(*end, 0.5)));
+        }
+        assert!(!inside(Vec2::new(2.0, -1.0)));
+        assert!(!inside(Vec2::new(4.0, 4.0)));
+        assert!(!inside(Vec2::new(5.0, 0.0)));
+    }
+
+    #[test]
+    #[cfg(feature = "bevy")]
+    fn test_push_transforms() {
+        let mut path = PLPath::new(vec![Vec2::ZERO]);
+        path.push_transforms([
+            Transform::from_xyz(1.0, 2.0, 3.0),
+            Transform::from_xyz(-4.0, 5.0, -6.0),
+        ]);
+        assert_eq!(
+            path.nodes,
+            vec![Vec2::ZERO, Vec2::new(1.0, 2.0), Vec2::new(-4.0, 5.0)]
+        );
+
+        let puncture_points = vec![PuncturePoint::new(Vec2::new(2.0, 0.0), 'A')];
+        let mut path_type = PathType::new(Vec2::new(0.0, 1.0), puncture_points);
+        path_type.push_transforms([
+            Transform::from_xyz(4.0, 1.0, 0.0),
+            Transform::from_xyz(4.0, -1.0, 0.0),
+            Transform::from_xyz(0.0, -1.0, 0.0),
+        ]);
+        assert_eq!(path_type.current_path.nodes.len(), 4);
+        assert_eq!(path_type.word_as_str(), "a");
+    }
+
+    #[test]
+    fn test_winding_vector() {
+        let puncture_points: Arc<[PuncturePoint]> = vec![
+            PuncturePoint::new(Vec2::new(0.0, 0.0), 'A'),
+            PuncturePoint::new(Vec2::new(10.0, 0.0), 'B'),
+            PuncturePoint::new(Vec2::new(20.0, 0.0), 'C'),
+        ]
+        .into();
+        let path = PLPath::new(vec![
+            Vec2::new(8.0, 2.0),
+            Vec2::new(12.0, 2.0),
+            Vec2::new(12.0, -2.0),
+            Vec2::new(8.0, -2.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points);
+        assert_eq!(path_type.winding_vector(), vec![0, 1, 0]);
+    }
+
+    #[test]
+    fn test_enclosed_punctures() {
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::new(0.0, 0.0), 'A'),
+            PuncturePoint::new(Vec2::new(10.0, 0.0), 'B'),
+            PuncturePoint::new(Vec2::new(20.0, 0.0), 'C'),
+            PuncturePoint::new(Vec2::new(30.0, 0.0), 'D'),
+        ];
+        let path = PLPath::new(vec![
+            Vec2::new(5.0, 2.0),
+            Vec2::new(5.0, -2.0),
+            Vec2::new(25.0, -2.0),
+            Vec2::new(25.0, 2.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points.into());
+        assert_eq!(
+            path_type.enclosed_punctures(FillRule::NonZero),
+            vec!['B', 'C']
+        );
+    }
+
+    #[test]
+    fn test_crossing_counts() {
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::ZERO, 'A'),
+            PuncturePoint::new(Vec2::new(10.0, 0.0), 'B'),
+        ];
+        let mut path_type = PathType::new(Vec2::new(-1.0, 1.0), puncture_points);
+        path_type.push(&Vec2::new(1.0, 1.0));
+        path_type.push(&Vec2::new(-1.0, 1.0));
+        assert_eq!(path_type.word_as_str(), "");
+        assert_eq!(path_type.crossing_counts().get(&'A'), Some(&2));
+        assert_eq!(path_type.crossing_counts().get(&'B'), None);
+    }
+
+    #[test]
+    fn test_taut_length() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::new(10.0, 0.0), 'A')];
+        let path = PLPath::new(vec![
+            Vec2::new(0.0, 1.0),
+            Vec2::new(12.0, 1.0),
+            Vec2::new(12.0, -1.0),
+            Vec2::new(0.0, -1.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points.into());
+        assert_eq!(path_type.word_as_str(), "a");
+
+        let approach = Vec2::new(0.0, 1.0).distance(Vec2::new(10.0, 0.0)) - 1.0;
+        let expected = approach.mul_add(2.0, std::f32::consts::TAU);
+        assert!((path_type.taut_length(1.0) - expected).abs() < 1e-4);
+
+        let trivial = PathType::new(Vec2::ZERO, vec![PuncturePoint::new(Vec2::X, 'A')]);
+        assert_eq!(trivial.taut_length(1.0), 0.0);
+    }
+
+    #[test]
+    fn test_identity_and_group_operations() {
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::new(0.0, 0.0), 'A'),
+            PuncturePoint::new(Vec2::new(10.0, 0.0), 'B'),
+        ];
+        let basepoint = Vec2::new(-5.0, 5.0);
+        let path = PLPath::new(vec![
+            basepoint,
+            Vec2::new(5.0, 5.0),
+            Vec2::new(5.0, 3.0),
+            Vec2::new(15.0, 3.0),
+            Vec2::new(15.0, -5.0),
+            Vec2::new(-5.0, -5.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points.clone().into());
+        assert_eq!(path_type.word_as_str(), "ab");
+
+        let identity = PathType::identity(basepoint, puncture_points);
+        assert_eq!(identity.word_as_str(), "");
+        assert_eq!((&identity * &path_type).word_as_str(), "ab");
+        assert_eq!((&path_type * &identity).word_as_str(), "ab");
+        assert_eq!((-&path_type).word_as_str(), "BA");
+        assert_eq!((&path_type * &-&path_type).word_as_str(), "");
+    }
+
+    #[test]
+    fn test_group_operations_keep_orientation() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]);
+        let basepoint = Vec2::new(-1.0, -1.0);
+        let a = PathType::from_path(
+            PathType::generator_loop(&puncture_points[0], 0.5, basepoint),
+            puncture_points.clone().into(),
+        )
+        .with_orientation(OrientationConvention::CounterclockwisePositive);
+        let b = PathType::from_path(
+            PathType::generator_loop(&puncture_points[1], 0.5, basepoint),
+            puncture_points.into(),
+        )
+        .with_orientation(OrientationConvention::CounterclockwisePositive);
+        assert_eq!(a.word_as_str(), "A");
+
+        for derived in [
+            &a * &b,
+            -&a,
+            a.change_basepoint(&PLPath::line(Vec2::new(-2.0, -3.0), basepoint)),
+        ] {
+            assert_eq!(
+                derived.orientation(),
+                OrientationConvention::CounterclockwisePositive
+            );
+        }
+        assert_eq!((&a * &b).word_as_str(), "AB");
+        assert_eq!((-&a).word_as_str(), "a");
+        assert_eq!((&a * &a).letters(), letters("aa"));
+    }
+
+    #[test]
+    fn test_validate_and_repair() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'A')];
+        let path = PLPath::new(vec![
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+        ]);
+        let mut path_type = PathType::from_path(path, puncture_points.into());
+        assert!(path_type.validate());
+
+        path_type.letters = letters("AAb");
+        assert!(!path_type.validate());
+        path_type.repair();
+        assert!(path_type.validate());
+        assert_eq!(path_type.word_as_str(), "a");
+    }
+
+    #[test]
+    fn test_winding_field() {
+        let path = PLPath::new(vec![
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ]);
+        let points: Vec<Vec2> = (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| Vec2::new(x as f32, y as f32) * 2.0))
+            .collect();
+        let field = path.winding_field(&points);
+        assert_eq!(field, vec![0, 0, 0, 0, 1, 0, 0, 0, 0]);
+        for (point, winding) in points.iter().zip(field) {
+            assert_eq!(path.winding_number(point), winding);
+        }
+    }
+
+    #[test]
+    fn test_open_path_word() {
+        let puncture_points: Arc<[PuncturePoint]> = vec![
+            PuncturePoint::new(Vec2::new(0.0, 0.0), 'A'),
+            PuncturePoint::new(Vec2::new(10.0, 0.0), 'B'),
+        ]
+        .into();
+        let path = PLPath::new(vec![
+            Vec2::new(-5.0, 5.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(5.0, -5.0),
+            Vec2::new(15.0, -5.0),
+        ]);
+        let as_loop = PathType::from_path(path.clone(), puncture_points.clone());
+        let as_open = PathType::from_path(path, puncture_points).with_openness(Openness::Open);
+        assert_eq!(as_loop.word_as_str(), "");
+        assert_eq!(as_open.word_as_str(), "a");
+    }
+
+    #[test]
+    fn test_segment_winding() {
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::new(0.0, 0.0), 'A'),
+            PuncturePoint::new(Vec2::new(0.0, 5.0), 'B'),
+            PuncturePoint::new(Vec2::new(0.0, 20.0), 'C'),
+        ];
+        let path_type = PathType::new(Vec2::ZERO, puncture_points);
+        let (left, right) = (Vec2::new(-1.0, 10.0), Vec2::new(1.0, 10.0));
+        assert_eq!(
+            path_type.segment_winding(left, right),
+            vec![('A', 1), ('B', 1)]
+        );
+        assert_eq!(
+            path_type.segment_winding(right, left),
+            vec![('A', -1), ('B', -1)]
+        );
+        assert!(path_type
+            .segment_winding(Vec2::new(0.0, -1.0), Vec2::new(0.0, 30.0))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_fill_rules_disagree_on_double_loop() {
+        let square = [
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ];
+        let path = PLPath::new([square, square].concat());
+        assert_eq!(path.winding_number(&Vec2::ZERO), 2);
+        assert!(path.contains_point(&Vec2::ZERO, FillRule::NonZero));
+        assert!(!path.contains_point(&Vec2::ZERO, FillRule::EvenOdd));
+
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'A')];
+        let path_type = PathType::from_path(path, puncture_points.into());
+        assert_eq!(path_type.enclosed_punctures(FillRule::NonZero), vec!['A']);
+        assert!(path_type.enclosed_punctures(FillRule::EvenOdd).is_empty());
+    }
+
+    #[test]
+    fn test_puncture_grid() {
+        let grid = PuncturePoint::grid(2, 2, Vec2::new(10.0, 20.0), Vec2::new(-5.0, 0.0));
+        let positions: Vec<Vec2> = grid.iter().map(|p| *p.position()).collect();
+        let names: Vec<char> = grid.iter().map(PuncturePoint::name).collect();
+        assert_eq!(
+            positions,
+            vec![
+                Vec2::new(-5.0, 0.0),
+                Vec2::new(5.0, 0.0),
+                Vec2::new(-5.0, 20.0),
+                Vec2::new(5.0, 20.0),
+            ]
+        );
+        assert_eq!(names, vec!['A', 'B', 'C', 'D']);
+    }
+
+    #[test]
+    fn test_random_in_box_is_seeded() {
+        let (min, max) = (Vec2::new(-100.0, -50.0), Vec2::new(100.0, 50.0));
+        let first = PuncturePoint::random_in_box(min, max, 7, 42);
+        let second = PuncturePoint::random_in_box(min, max, 7, 42);
+        assert_eq!(first, second);
+        assert_ne!(first, PuncturePoint::random_in_box(min, max, 7, 43));
+
+        // 7 punctures make a 3x3 grid of 66.7x33.3 cells.
+        let min_separation = 0.5 * (100.0 / 3.0);
+        for (i, a) in first.iter().enumerate() {
+            assert!(a.position().cmpge(min).all() && a.position().cmple(max).all());
+            for b in &first[i + 1..] {
+                assert_ne!(a.name(), b.name());
+                assert!(a.position().distance(*b.position()) >= min_separation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_puncture_point_sorting() {
+        let mut puncture_points = [
+            PuncturePoint::new(Vec2::new(0.0, 0.0), 'C'),
+            PuncturePoint::new(Vec2::new(1.0, 0.0), 'a'),
+            PuncturePoint::new(Vec2::new(2.0, 0.0), 'D'),
+            PuncturePoint::new(Vec2::new(3.0, 0.0), 'B'),
+        ];
+        puncture_points.sort();
+        let names: Vec<char> = puncture_points.iter().map(PuncturePoint::name).collect();
+        assert_eq!(names, vec!['A', 'B', 'C', 'D']);
+    }
+
+    #[test]
+    fn test_puncture_point_ordering_includes_shape() {
+        let point = PuncturePoint::new(Vec2::ZERO, 'A');
+        let wide = point.with_shape(PunctureShape::Rect {
+            half_size: Vec2::new(2.0, 1.0),
+        });
+        let tall = point.with_shape(PunctureShape::Rect {
+            half_size: Vec2::new(1.0, 2.0),
+        });
+        assert_ne!(point.cmp(&wide), Ordering::Equal);
+        assert_ne!(wide.cmp(&tall), Ordering::Equal);
+        assert_eq!(wide.cmp(&wide), Ordering::Equal);
+
+        let mut puncture_points = vec![wide, point, tall, wide];
+        puncture_points.sort();
+        puncture_points.dedup();
+        assert_eq!(puncture_points, [point, tall, wide]);
+        let set: std::collections::BTreeSet<_> = [point, wide, tall, wide].into_iter().collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_puncture_point_equality_matches_ordering() {
+        let zero = PuncturePoint::new(Vec2::ZERO, 'A');
+        let negative_zero = PuncturePoint::new(Vec2::new(-0.0, 0.0), 'A');
+        assert_ne!(zero, negative_zero);
+        assert_ne!(zero.cmp(&negative_zero), Ordering::Equal);
+
+        let nan = PuncturePoint::new(Vec2::new(f32::NAN, 0.0), 'A');
+        assert_eq!(nan, nan);
+        assert_eq!(nan.cmp(&nan), Ordering::Equal);
+
+        for (a, b) in [(zero, negative_zero), (zero, nan), (nan, nan), (zero, zero)] {
+            assert_eq!(a == b, a.cmp(&b) == Ordering::Equal);
+        }
+    }
+
+    #[test]
+    fn test_non_ascii_puncture_names() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'é')];
+        assert_eq!(puncture_points[0].name(), 'É');
+        let generator = PathType::from_path(
+            PathType::generator_loop(&puncture_points[0], 0.5, Vec2::new(-2.0, -1.0)),
+            puncture_points.into(),
+        );
+        assert_eq!(generator.word_as_str(), "é");
+        let inverse = -&generator;
+        assert_eq!(inverse.word_as_str(), "É");
+        assert_eq!(crate::word::invert_word(generator.word_as_str()), "É");
+        assert!(!crate::word::is_reduced(
+            &[generator.word(), inverse.word()].concat()
+        ));
+    }
+
+    #[test]
+    fn test_auto_named() {
+        let puncture_points = PuncturePoint::auto_named((0..5).map(|i| Vec2::new(i as f32, 0.0)));
+        let names: String = puncture_points.iter().map(PuncturePoint::name).collect();
+        assert_eq!(names, "ABCDE");
+        assert_eq!(puncture_points[3].position(), &Vec2::new(3.0, 0.0));
+    }
+
+    #[test]
+    fn test_update_word_skips_clean_path() {
+        let recomputations = || WORD_RECOMPUTATIONS.with(std::cell::Cell::get);
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'A')];
+        let mut path_type = PathType::new(Vec2::new(-1.0, 1.0), puncture_points);
+        // Leave the word stale after pushing, so `update_word` has work to do.
+        path_type.defer_word = true;
+        path_type.push(&Vec2::new(1.0, 1.0));
+        path_type.push(&Vec2::new(1.0, -1.0));
+
+        let before = recomputations();
+        assert_eq!(path_type.update_word(), "a");
+        assert_eq!(path_type.update_word(), "a");
+        assert_eq!(recomputations(), before + 1);
+
+        path_type.push(&Vec2::new(-1.0, -1.0));
+        assert_eq!(path_type.update_word(), "a");
+        assert_eq!(path_type.update_word(), "a");
+        assert_eq!(recomputations(), before + 2);
+    }
+
+    #[test]
+    fn test_contains_subloop() {
+        let puncture_points =
+            PuncturePoint::auto_named((0..4).map(|i| Vec2::new(2.0 * i as f32, 0.0)));
+        let with_word = |word: &str| loop_with_word(word, &puncture_points);
+        let needle = with_word("ab");
+        let haystack = with_word("cabd");
+        assert_eq!(haystack.word_as_str(), "cabd");
+        assert!(haystack.contains_subloop(&needle));
+        assert!(!with_word("acbd").contains_subloop(&needle));
+        assert!(haystack.contains_subloop(&with_word("")));
+
+        let moved = PuncturePoint::auto_named((0..4).map(|i| Vec2::new(2.0 * i as f32, 5.0)));
+        let elsewhere = loop_with_word("cabd", &moved);
+        assert_eq!(elsewhere.word_as_str(), "cabd");
+        assert!(!elsewhere.contains_subloop(&needle));
+    }
+
+    #[test]
+    fn test_abelian_distance() {
+        let puncture_points: Arc<[PuncturePoint]> = vec![
+            PuncturePoint::new(Vec2::ZERO, 'A'),
+            PuncturePoint::new(Vec2::new(10.0, 0.0), 'B'),
+        ]
+        .into();
+        let square = [
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ];
+        let once = PathType::from_path(PLPath::new(square), puncture_points.clone());
+        let twice = PathType::from_path(PLPath::new([square, square].concat()), puncture_points);
+        assert_eq!(once.abelian_distance(&twice), 1);
+        assert_eq!(twice.abelian_distance(&-&once), 3);
+        assert_eq!(once.abelian_distance(&once), 0);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_homotopy_session_round_trip() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::new(0.0, 1.0), 'A')];
+        let mut path_type = PathType::new(Vec2::new(-5.0, 5.0), puncture_points);
+        let mut session = HomotopySession::new(&path_type);
+        for point in [
+            Vec2::new(5.0, 5.0),
+            Vec2::new(5.0, -5.0),
+            Vec2::new(-5.0, -5.0),
+            Vec2::new(-5.0, 5.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(5.0, -5.0),
+            Vec2::new(5.0, 5.0),
+        ] {
+            path_type.push(&point);
+            path_type.record_into(&mut session);
+        }
+        assert_eq!(session.word_history, vec!["", "a", "aa", "a"]);
+
+        let serialized = ron::to_string(&session).expect("session serializes");
+        let loaded: HomotopySession = ron::from_str(&serialized).expect("session deserializes");
+        assert_eq!(loaded, session);
+        assert_eq!(loaded.to_path_type().word(), path_type.word());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_path_type_loads_data_without_settings() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::new(0.0, 1.0), 'A')];
+        let path = PLPath::new([
+            Vec2::new(-5.0, 5.0),
+            Vec2::new(5.0, 5.0),
+            Vec2::new(5.0, -5.0),
+            Vec2::new(-5.0, -5.0),
+        ]);
+        // Only the fields written before any of the settings were serialized.
+        let serialized = format!(
+            "(path: {}, puncture_points: {}, crossing_counts: [])",
+            ron::to_string(&path).expect("path serializes"),
+            ron::to_string(&puncture_points).expect("puncture points serialize"),
+        );
+        let loaded: PathType = ron::from_str(&serialized).expect("path type deserializes");
+        let expected = PathType::from_path(path, puncture_points.into());
+        assert_eq!(loaded.word(), "a");
+        assert_eq!(loaded.word(), expected.word());
+        assert_eq!(loaded.openness, Openness::default());
+        assert_eq!(loaded.wrap, Wrap::default());
+        assert_eq!(loaded.orientation, OrientationConvention::default());
+        assert!(loaded.word_history.is_empty());
+        assert_eq!(loaded.timestamps(), [Duration::ZERO; 4]);
+
+        let round_trip: PathType =
+            ron::from_str(&ron::to_string(&loaded).expect("path type serializes"))
+                .expect("path type deserializes");
+        assert_eq!(round_trip.word(), loaded.word());
+        assert_eq!(round_trip.timestamps(), loaded.timestamps());
+    }
+
+    #[test]
+    fn test_segment_winding_sweep_order() {
+        let puncture_points: Arc<[PuncturePoint]> = vec![
+            PuncturePoint::new(Vec2::new(2.0, 0.0), 'b'),
+            PuncturePoint::new(Vec2::new(3.0, 0.0), 'c'),
+            PuncturePoint::new(Vec2::new(1.0, 0.0), 'a'),
+        ]
+        .into();
+        let (left, right) = (Vec2::new(0.0, 1.0), Vec2::new(4.0, 1.0));
+        let forward = PathType::from_path(PLPath::line(left, right), puncture_points.clone())
+            .with_openness(Openness::Open);
+        let backward = PathType::from_path(PLPath::line(right, left), puncture_points)
+            .with_openness(Openness::Open);
+        assert_eq!(forward.word_as_str(), "abc");
+        assert_eq!(backward.word_as_str(), "CBA");
+        assert_eq!(backward.word_as_str(), crate::word::invert_word("abc"));
+    }
+
+    #[test]
+    fn test_recent_words() {
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::new(1.0, 0.0), 'a'),
+            PuncturePoint::new(Vec2::new(3.0, 0.0), 'b'),
+            PuncturePoint::new(Vec2::new(5.0, 0.0), 'c'),
+        ];
+        let mut path_type = PathType::new(Vec2::new(0.0, 1.0), puncture_points)
+            .with_openness(Openness::Open)
+            .with_word_history(3);
+        assert_eq!(path_type.recent_words(), &[""]);
+
+        // Each step passes over one more puncture point, changing the word.
+        for x in [2.0, 4.0, 6.0] {
+            path_type.push(&Vec2::new(x, 1.0));
+        }
+        assert_eq!(path_type.recent_words(), &["a", "ab", "abc"]);
+    }
+
+    #[test]
+    fn test_snapshot_is_unaffected_by_later_pushes() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::new(1.0, 0.0), 'a')];
+        let mut path_type =
+            PathType::new(Vec2::new(0.0, 1.0), puncture_points).with_openness(Openness::Open);
+        path_type.push(&Vec2::new(2.0, 1.0));
+
+        let snapshot = path_type.snapshot();
+        let handle = std::thread::spawn(move || snapshot);
+
+        path_type.push(&Vec2::new(2.0, -1.0));
+        path_type.push(&Vec2::new(0.0, -1.0));
+
+        let snapshot = handle.join().expect("snapshot thread panicked");
+        assert_eq!(
+            snapshot.nodes,
+            vec![Vec2::new(0.0, 1.0), Vec2::new(2.0, 1.0)]
+        );
+        assert_eq!(snapshot.word, "a");
+        assert_eq!(snapshot.openness, Openness::Open);
+        assert_eq!(snapshot.compute_word(), "a");
+        assert_eq!(snapshot.punctures, path_type.puncture_points.to_vec());
+        assert_eq!(path_type.current_path.nodes.len(), 4);
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        // Crossing.
+        assert_eq!(
+            segment_intersection(
+                Vec2::new(-1.0, 0.0),
+                Vec2::new(1.0, 0.0),
+                Vec2::new(0.0, -1.0),
+                Vec2::new(0.0, 1.0)
+            ),
+            Some(Vec2::ZERO)
+        );
+        // Parallel.
+        assert_eq!(
+            segment_intersection(Vec2::ZERO, Vec2::X, Vec2::Y, Vec2::ONE),
+            None
+        );
+        // Collinear and overlapping, in either direction.
+        let (b0, b1) = (Vec2::new(3.0, 0.0), Vec2::new(1.0, 0.0));
+        assert_eq!(
+            segment_intersection(Vec2::ZERO, Vec2::new(2.0, 0.0), b0, b1),
+            Some(Vec2::X)
+        );
+        assert_eq!(
+            segment_intersection(Vec2::new(2.0, 0.0), Vec2::ZERO, b0, b1),
+            Some(Vec2::new(2.0, 0.0))
+        );
+        // Collinear but disjoint.
+        assert_eq!(
+            segment_intersection(Vec2::ZERO, Vec2::X, Vec2::new(2.0, 0.0), b0),
+            None
+        );
+        // Touching at an endpoint.
+        assert_eq!(
+            segment_intersection(Vec2::ZERO, Vec2::X, Vec2::X, Vec2::ONE),
+            Some(Vec2::X)
+        );
+        assert_eq!(
+            segment_intersection(Vec2::ZERO, Vec2::new(2.0, 0.0), Vec2::X, Vec2::ONE),
+            Some(Vec2::X)
+        );
+    }
+
+    #[test]
+    fn test_cone_point_order() {
+        let square = [
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ];
+        let twice_around = PLPath::new([square, square].concat());
+        let puncture = PuncturePoint::new(Vec2::ZERO, 'a');
+
+        let plain = PathType::from_path(twice_around.clone(), vec![puncture].into());
+        assert_eq!(plain.word_as_str(), "aa");
+        let cone = PathType::from_path(twice_around, vec![puncture.with_order(2)].into());
+        assert_eq!(cone.word_as_str(), "");
+    }
+
+    /// Only uses glam and this crate, so this also runs with `--no-default-features`.
+    mod without_bevy {
+        use crate::piecewise_linear::{PLPath, PathType, PuncturePoint};
+        use glam::Vec2;
+
+        #[test]
+        fn test_core_computes_word() {
+            let puncture_points = PuncturePoint::auto_named([Vec2::ZERO]);
+            let path = PLPath::new([
+                Vec2::new(-1.0, 1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(-1.0, -1.0),
+            ]);
+            let path_type = PathType::from_path(path, puncture_points.into());
+            assert_eq!(path_type.word_as_str(), "a");
+        }
+    }
+
+    #[test]
+    fn test_is_convex() {
+        let square = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(-1.0, 1.0),
+        ]);
+        assert!(square.is_convex());
+        assert!(square.reverse().is_convex());
+
+        let chevron = PLPath::new([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(4.0, 0.0),
+            Vec2::new(2.0, 1.0),
+        ]);
+        assert!(!chevron.is_convex());
+        assert!(!PLPath::line(Vec2::ZERO, Vec2::X).is_convex());
+    }
+
+    #[test]
+    fn test_push_at_records_timestamps() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'a')];
+        let mut path_type = PathType::new(Vec2::new(-1.0, 1.0), puncture_points);
+        path_type.push_at(&Vec2::new(1.0, 1.0), Duration::from_secs(1));
+        path_type.push_at(&Vec2::new(1.0, -1.0), Duration::from_secs(2));
+        path_type.push(&Vec2::new(-1.0, -1.0));
+        assert_eq!(
+            path_type.timestamps(),
+            [0, 1, 2, 2].map(Duration::from_secs)
+        );
+
+        // Pulling the path tight pops the nodes below the puncture point, along with their timestamps.
+        path_type.push_at(&Vec2::new(2.0, 1.0), Duration::from_secs(4));
+        assert_eq!(
+            path_type.current_path.nodes.len(),
+            path_type.timestamps().len()
+        );
+        assert_eq!(path_type.timestamps().last(), Some(&Duration::from_secs(4)));
+        assert!(!path_type.timestamps().contains(&Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_support() {
+        let puncture_points: Arc<[PuncturePoint]> = vec![
+            PuncturePoint::new(Vec2::new(1.0, 0.0), 'a'),
+            PuncturePoint::new(Vec2::new(3.0, 0.0), 'c'),
+        ]
+        .into();
+        // Over `a`, back over `a`, then under `a` and up over `c`: `aAc`, which reduces to `c`.
+        let path = PLPath::new([
+            Vec2::new(0.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(0.0, 1.0),
+            Vec2::new(0.0, -1.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(4.0, 1.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points).with_openness(Openness::Open);
+        assert_eq!(path_type.word_as_str(), "c");
+        assert_eq!(path_type.support(), HashSet::from(['C']));
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::new(1.0, 0.0), 'a'),
+            PuncturePoint::new(Vec2::new(1.0, 5.0), 'b'),
+            PuncturePoint::new(Vec2::new(3.0, 1.0), 'c'),
+        ];
+        let mut path_type = PathType::new(Vec2::new(2.0, 1.0), puncture_points);
+        assert_eq!(path_type.diagnose(), vec![Diagnostic::SharedX('A', 'B')]);
+
+        path_type.push(&Vec2::new(4.0, 1.0));
+        assert_eq!(
+            path_type.diagnose(),
+            vec![
+                Diagnostic::SharedX('A', 'B'),
+                Diagnostic::PunctureOnPath('C')
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_labels() {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        enum Color {
+            Red,
+            Blue,
+        }
+
+        impl std::fmt::Display for Color {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    Self::Red => write!(f, "Red"),
+                    Self::Blue => write!(f, "Blue"),
+                }
+            }
+        }
+
+        impl Label for Color {}
+
+        let puncture_points: Arc<[PuncturePoint<Color>]> = vec![
+            PuncturePoint::labelled(Vec2::new(1.0, 0.0), Color::Red),
+            PuncturePoint::labelled(Vec2::new(3.0, 0.0), Color::Blue),
+        ]
+        .into();
+        // Over `Red`, then back under `Red` and over `Blue` from right to left.
+        let path = PLPath::new([
+            Vec2::new(0.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(4.0, -1.0),
+            Vec2::new(4.0, 1.0),
+            Vec2::new(2.0, 1.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points).with_openness(Openness::Open);
+        assert_eq!(path_type.letters(), [(Color::Red, 1), (Color::Blue, -1)]);
+        assert_eq!(path_type.word_as_str(), "Red Blue^-1");
+        assert_eq!(
+            path_type.support(),
+            HashSet::from([Color::Red, Color::Blue])
+        );
+        // Products keep the openness, so the inverse cancels for the loop.
+        let as_loop = path_type.with_openness(Openness::Loop);
+        assert_eq!((&as_loop * &-&as_loop).letters(), []);
+
+        // An order of 1 makes going around `Red` trivial.
+        let trivial_red = PathType::from_path(
+            PLPath::new([Vec2::new(0.0, 1.0), Vec2::new(2.0, 1.0)]),
+            vec![PuncturePoint::labelled(Vec2::new(1.0, 0.0), Color::Red).with_order(1)].into(),
+        )
+        .with_openness(Openness::Open);
+        assert_eq!(trivial_red.word_as_str(), "");
+    }
+
+    #[test]
+    fn test_wrap_across_seam() {
+        let puncture_points: Arc<[PuncturePoint]> = vec![
+            PuncturePoint::new(Vec2::new(9.5, 0.0), 'a'),
+            PuncturePoint::new(Vec2::new(5.0, 0.0), 'b'),
+            PuncturePoint::new(Vec2::new(0.5, 0.0), 'c'),
+        ]
+        .into();
+        let wrap = Wrap {
+            x: Some(10.0),
+            y: None,
+        };
+
+        // Stepping right across the seam passes over `a` and `c`, not back over `b`.
+        let step = PLPath::line(Vec2::new(8.0, 1.0), Vec2::new(1.0, 1.0));
+        let plane = PathType::from_path(step.clone(), puncture_points.clone())
+            .with_openness(Openness::Open);
+        assert_eq!(plane.word_as_str(), "B");
+        let wrapped = PathType::from_path(step, puncture_points.clone())
+            .with_openness(Openness::Open)
+            .with_wrap(wrap);
+        assert_eq!(wrapped.word_as_str(), "ac");
+
+        // A clockwise loop around the seam, recorded node by node.
+        let mut path_type =
+            PathType::new(Vec2::new(9.0, 1.0), puncture_points.to_vec()).with_wrap(wrap);
+        for node in [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(9.0, -1.0),
+        ] {
+            path_type.push(&node);
+        }
+        assert_eq!(path_type.word_as_str(), "ac");
+        assert_eq!(path_type.crossing_counts().get(&'B'), None);
+    }
+
+    #[test]
+    fn test_concatenate_across_seam() {
+        let puncture_points: Arc<[PuncturePoint]> = vec![
+            PuncturePoint::new(Vec2::new(9.5, 0.0), 'a'),
+            PuncturePoint::new(Vec2::new(5.0, 0.0), 'b'),
+        ]
+        .into();
+        let wrap = Wrap {
+            x: Some(10.0),
+            y: None,
+        };
+        // A clockwise loop around `a` through the seam, and a clockwise loop around `b`.
+        let around_a = PathType::from_path(
+            PLPath::new([
+                Vec2::new(9.0, 1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(1.0, -1.0),
+                Vec2::new(9.0, -1.0),
+            ]),
+            puncture_points.clone(),
+        )
+        .with_wrap(wrap);
+        let around_b = PathType::from_path(
+            PLPath::new([
+                Vec2::new(9.0, 1.0),
+                Vec2::new(9.0, 2.0),
+                Vec2::new(6.0, 2.0),
+                Vec2::new(6.0, -1.0),
+                Vec2::new(4.0, -1.0),
+                Vec2::new(4.0, 1.0),
+            ]),
+            puncture_points,
+        )
+        .with_wrap(wrap);
+        assert_eq!(around_a.word_as_str(), "a");
+        assert_eq!(around_b.word_as_str(), "b");
+
+        let product = &around_a * &around_b;
+        assert_eq!(product.wrap(), wrap);
+        assert_eq!(product.word_as_str(), "ab");
+        assert_eq!((-&around_a).word_as_str(), "A");
+        assert_eq!(
+            around_a.concatenate(&around_a.current_path).word_as_str(),
+            "aa"
+        );
+    }
+
+    #[test]
+    fn test_merge_close() {
+        let points = PuncturePoint::auto_named([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(50.0, 0.0),
+            Vec2::new(0.5, 0.5),
+            Vec2::new(1.2, 0.0),
+        ]);
+        let merged = PuncturePoint::merge_close(&points, 1.0);
+        assert_eq!(merged, vec![points[0], points[1]]);
+        assert_eq!(PuncturePoint::merge_close(&points, 0.1), points);
+    }
+
+    #[test]
+    fn test_push_if_moved() {
+        let mut path_type = PathType::<char>::new(Vec2::ZERO, vec![]);
+        assert!(!path_type.push_if_moved(&Vec2::new(0.05, 0.0), 0.1));
+        assert_eq!(path_type.current_path.nodes, vec![Vec2::ZERO]);
+        assert!(path_type.push_if_moved(&Vec2::new(0.5, 0.0), 0.1));
+        assert_eq!(path_type.current_path.end(), &Vec2::new(0.5, 0.0));
+        assert!(!path_type.push_if_moved(&Vec2::new(0.5, 0.0), 0.0));
+    }
+
+    #[test]
+    fn test_try_concatenate() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(2.0, 0.0)]);
+        let path = |nodes: Vec<Vec2>, puncture_points: &[PuncturePoint]| {
+            PathType::from_path(PLPath::new(nodes), puncture_points.into())
+        };
+        let first = path(
+            vec![Vec2::new(-1.0, -1.0), Vec2::new(-1.0, 1.0)],
+            &puncture_points,
+        );
+        let second = path(
+            vec![Vec2::new(-1.0, 1.0), Vec2::new(1.0, 1.0)],
+            &puncture_points,
+        );
+        let concatenated = first.try_concatenate(&second).expect("same punctures");
+        assert_eq!(concatenated.current_path, (&first * &second).current_path);
+
+        let elsewhere = path(
+            vec![Vec2::new(-1.0, 1.0), Vec2::new(1.0, 1.0)],
+            &puncture_points[..1],
+        );
+        assert!(matches!(
+            first.try_concatenate(&elsewhere),
+            Err(PunctureMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_puncture_shapes() {
+        let skirting = |shape: PunctureShape| {
+            let puncture_points = [PuncturePoint::new(Vec2::new(0.0, 2.0), 'A').with_shape(shape)];
+            let mut path_type = PathType::new(Vec2::new(-5.0, 1.0), puncture_points.to_vec())
+                .with_openness(Openness::Open);
+            path_type.push(&Vec2::ZERO);
+            path_type.push(&Vec2::new(5.0, 1.0));
+            path_type.current_path.nodes.len()
+        };
+        let wide = PunctureShape::Rect {
+            half_size: Vec2::new(4.0, 1.5),
+        };
+        assert_eq!(skirting(wide), 3);
+        assert_eq!(skirting(PunctureShape::Circle { radius: 0.1 }), 2);
+        let flat = PunctureShape::Ellipse {
+            half_axes: Vec2::new(4.0, 1.5),
+        };
+        assert_eq!(skirting(flat), 3);
+    }
+
+    #[test]
+    fn test_to_turn_commands() {
+        let path = PLPath::new([
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 4.0),
+            Vec2::new(1.0, 4.0),
+            Vec2::new(-2.0, 0.0),
+            Vec2::new(5.0, -2.0),
+        ]);
+        let commands = path.to_turn_commands();
+        assert!(matches!(commands.first(), Some(TurnCommand::Turn(_))));
+
+        let (mut position, mut angle) = (path.nodes[0], 0.0_f32);
+        let mut nodes = vec![position];
+        for command in commands {
+            match command {
+                TurnCommand::Turn(turn) => angle += turn,
+                TurnCommand::Forward(distance) => {
+                    position += Vec2::from_angle(angle) * distance;
+                    nodes.push(position);
+                }
+            }
+        }
+        let distinct = [path.nodes[0], path.nodes[1], path.nodes[3], path.nodes[4]];
+        assert_eq!(nodes.len(), distinct.len());
+        for (node, expected) in nodes.iter().zip(distinct) {
+            assert!(node.distance(expected) < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_winding_epsilon() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'A')];
+        let grazing = |epsilon: f32| {
+            PathType::new(Vec2::new(-1.0, 1e-4), puncture_points.clone())
+                .with_winding_epsilon(epsilon)
+                .segment_winding(Vec2::new(-1.0, 1e-4), Vec2::new(1.0, 1e-4))
+        };
+        assert_eq!(grazing(0.0), vec![('A', 1)]);
+        assert!(grazing(1e-3).is_empty());
+    }
+
+    #[test]
+    fn test_path_stream() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(4.0, 0.0)]);
+        let path_type =
+            PathType::new(Vec2::new(-2.0, 1.0), puncture_points).with_openness(Openness::Open);
+        let mut stream = PathStream::new(path_type, 0.1);
+        let changes: Vec<Option<WordChange>> = [
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.05, 1.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(5.0, 1.0),
+        ]
+        .into_iter()
+        .map(|point| stream.feed(point))
+        .collect();
+        let change = |previous: &str, word: &str| {
+            Some(WordChange {
+                previous: previous.to_string(),
+                word: word.to_string(),
+            })
+        };
+        assert_eq!(
+            changes,
+            vec![None, change("", "a"), None, None, change("a", "ab")]
+        );
+        assert_eq!(stream.into_inner().word_as_str(), "ab");
+    }
+
+    #[test]
+    fn test_path_stream_with_deferred_word() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO]);
+        let mut path_type =
+            PathType::new(Vec2::new(-1.0, 1.0), puncture_points).with_openness(Openness::Open);
+        path_type.defer_word = true;
+        let mut stream = PathStream::new(path_type, 0.1);
+        assert_eq!(stream.feed(Vec2::new(-0.5, 1.0)), None);
+        assert_eq!(
+            stream.feed(Vec2::new(1.0, 1.0)),
+            Some(WordChange {
+                previous: String::new(),
+                word: "a".to_string(),
+            })
+        );
+        assert_eq!(stream.feed(Vec2::new(1.0, 1.05)), None);
+        assert_eq!(
+            stream.feed(Vec2::new(-1.0, 1.0)),
+            Some(WordChange {
+                previous: "a".to_string(),
+                word: String::new(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_complexity() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]);
+        assert_eq!(loop_with_word("", &puncture_points).complexity(), (0, 0));
+        let path_type = loop_with_word("aabb", &puncture_points);
+        assert_eq!(path_type.word_as_str(), "aabb");
+        assert_eq!(path_type.complexity(), (4, 2));
+    }
+
+    #[test]
+    fn test_closed_path() {
+        let nodes = [Vec2::ZERO, Vec2::X, Vec2::Y];
+        let closed = PLPath::closed(nodes);
+        assert_eq!(closed.nodes.len(), 4);
+        assert_eq!(closed.nodes.first(), closed.nodes.last());
+        assert_eq!(PLPath::closed(closed.nodes.clone()), closed);
+        assert!(PLPath::closed(Vec::new()).nodes.is_empty());
+    }
+
+    #[test]
+    fn test_winding_delta() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(5.0, 0.0)]);
+        let mut path_type = PathType::new(Vec2::new(-1.0, 1.0), puncture_points);
+        let start = path_type.snapshot();
+
+        let wind = [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ];
+        for point in &wind {
+            path_type.push(point);
+        }
+        let peak = path_type.winding_delta(&start);
+        assert_eq!(peak, HashMap::from([('A', 1), ('B', 0)]));
+
+        for point in wind.iter().rev().skip(1).chain([&Vec2::new(-1.0, 1.0)]) {
+            path_type.push(point);
+        }
+        let delta = path_type.winding_delta(&start);
+        assert_eq!(delta, HashMap::from([('A', 0), ('B', 0)]));
+    }
+
+    #[test]
+    fn test_to_animated_svg() {
+        let path = PLPath::new([Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(3.0, 4.0)]);
+        let svg = path.to_animated_svg(2.5);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("points=\"0,0 3,0 3,-4\""));
+        assert!(svg.contains(
+            "<animate attributeName=\"stroke-dashoffset\" from=\"7\" to=\"0\" dur=\"2.5s\""
+        ));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_separates() {
+        let puncture_points: Arc<[PuncturePoint]> =
+            PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(5.0, 0.0), Vec2::new(10.0, 0.0)])
+                .into();
+        let path = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points);
+        assert_eq!(path_type.separates(&'A', &'B'), Some(true));
+        assert_eq!(path_type.separates(&'B', &'A'), Some(true));
+        assert_eq!(path_type.separates(&'B', &'C'), Some(false));
+        assert_eq!(path_type.separates(&'A', &'Z'), None);
+    }
+
+    #[test]
+    fn test_generators_iter() {
+        let puncture_points =
+            PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(6.0, 0.0)]);
+        assert_eq!(
+            loop_with_word("", &puncture_points)
+                .generators_iter()
+                .count(),
+            0
+        );
+        for word in ["abAc", "aaB"] {
+            let path_type = loop_with_word(word, &puncture_points);
+            assert_eq!(path_type.word_as_str(), word);
+            assert_eq!(
+                path_type.generators_iter().collect::<Vec<_>>(),
+                letters(word)
+            );
+        }
+    }
+
+    #[test]
+    fn test_geometry_hash() {
+        let nodes = vec![Vec2::ZERO, Vec2::new(1.0, 2.0), Vec2::new(-3.0, 0.5)];
+        let path = PLPath::new(nodes.clone());
+        assert_eq!(
+            path.geometry_hash(),
+            PLPath::new(nodes.clone()).geometry_hash()
+        );
+
+        let mut moved = nodes;
+        moved[1].y += 0.001;
+        assert_ne!(path.geometry_hash(), PLPath::new(moved).geometry_hash());
+        assert_ne!(path.geometry_hash(), path.reverse().geometry_hash());
+    }
+
+    #[test]
+    fn test_signature() {
+        let nodes: Vec<Vec2> = (0..=40).map(|i| Vec2::new(i as f32, 0.0)).collect();
+        let corners = [
+            Vec2::ZERO,
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        // Translated copies with a little deterministic noise on every node.
+        let noisy = |nodes: &[Vec2], offset: Vec2| -> Vec<Vec2> {
+            nodes
+                .iter()
+                .enumerate()
+                .map(|(i, node)| {
+                    let i = i as f32;
+                    *node + offset + 0.02 * Vec2::new(i.cos(), i.sin())
+                })
+                .collect()
+        };
+        let (line, noisy_line) = (
+            PLPath::new(nodes.clone()),
+            PLPath::new(noisy(&nodes, Vec2::new(3.0, 5.0))),
+        );
+        let (square, noisy_square) = (
+            PLPath::closed(corners),
+            PLPath::closed(noisy(&corners, Vec2::new(-7.0, 2.0))),
+        );
+
+        assert_eq!(line.signature(8).len(), 8);
+        assert_eq!(line.signature(8), noisy_line.signature(8));
+        assert_eq!(square.signature(8), noisy_square.signature(8));
+        assert_ne!(line.signature(8), square.signature(8));
+        assert!(line.signature(0).is_empty());
+        assert_eq!(PLPath::new(vec![]).signature(4), vec![0; 4]);
+    }
+
+    #[test]
+    fn test_change_basepoint() {
+        let puncture_points: Arc<[PuncturePoint]> =
+            PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(-3.0, 0.0)]).into();
+        let path = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points);
+        assert_eq!(path_type.word_as_str(), "a");
+
+        // From below `B`, up around its left and over it to the basepoint.
+        let connecting = PLPath::new([
+            Vec2::new(-3.0, -2.0),
+            Vec2::new(-4.0, -2.0),
+            Vec2::new(-4.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ]);
+        let transported = path_type.change_basepoint(&connecting);
+        assert_eq!(transported.word_as_str(), "baB");
+        let back = transported.change_basepoint(&connecting.reverse());
+        assert_eq!(back.word_as_str(), path_type.word_as_str());
+    }
+
+    #[test]
+    fn test_segment_generators() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]);
+        let mut path_type = PathType::new(Vec2::new(-1.0, 1.0), puncture_points);
+        for point in [
+            Vec2::new(4.0, 1.0),
+            Vec2::new(4.0, -1.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(2.0, 1.0),
+        ] {
+            path_type.push(&point);
+        }
+        let segment_generators = path_type.segment_generators();
+        assert_eq!(
+            segment_generators.len(),
+            path_type.current_path.closed_segments().count()
+        );
+        let mut concatenated = segment_generators.concat();
+        reduce_letters(&mut concatenated, |_| None);
+        assert_eq!(concatenated, path_type.letters());
+        assert_eq!(path_type.word_as_str(), "abA");
+    }
+
+    #[test]
+    fn test_exact_backtrack_cancels() {
+        let puncture_points: Arc<[PuncturePoint]> =
+            PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]).into();
+        let out_and_back = [
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(4.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ];
+        for openness in [Openness::Loop, Openness::Open] {
+            // `from_path` does not tighten, so the word alone has to cancel the backtrack.
+            let path_type = PathType::from_path(PLPath::new(out_and_back), puncture_points.clone())
+                .with_openness(openness);
+            assert_eq!(path_type.current_path.nodes.len(), 3);
+            assert_eq!(
+                path_type.segment_generators()[..2],
+                [letters("ab"), letters("BA")]
+            );
+            assert_eq!(path_type.word_as_str(), "");
+
+            let mut pushed =
+                PathType::new(out_and_back[0], puncture_points.to_vec()).with_openness(openness);
+            pushed.push(&out_and_back[1]);
+            pushed.push(&out_and_back[2]);
+            assert_eq!(pushed.word_as_str(), "");
+        }
+    }
+
+    #[test]
+    fn test_enumerate_classes() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::X]);
+        assert_eq!(
+            PathType::enumerate_classes(&puncture_points, 1),
+            vec!["", "a", "A", "b", "B"]
+        );
+        // Reduced words of length `n` over two generators: `4 * 3^(n - 1)`.
+        let classes = PathType::enumerate_classes(&puncture_points, 2);
+        assert_eq!(classes.len(), 1 + 4 + 12);
+        assert_eq!(classes.iter().collect::<HashSet<_>>().len(), classes.len());
+        assert!(!classes.contains(&"aA".to_string()));
+    }
+
+    #[test]
+    fn test_ray_crossings() {
+        let square = PLPath::new([
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(-1.0, 1.0),
+        ]);
+        let origin = Vec2::new(0.0, 0.5);
+        // The square is counterclockwise, which is `-1` like an uppercase letter.
+        assert_eq!(
+            square.ray_crossings(origin, Vec2::Y),
+            vec![(Vec2::new(0.0, 1.0), -1)]
+        );
+        assert_eq!(
+            square.reverse().ray_crossings(origin, Vec2::Y),
+            vec![(Vec2::new(0.0, 1.0), 1)]
+        );
+        for direction in [Vec2::X, Vec2::new(-1.0, -3.0), Vec2::new(0.3, 1.0)] {
+            let crossings = square.ray_crossings(origin, direction);
+            assert_eq!(crossings.len(), 1);
+            let total: i32 = crossings.iter().map(|(_, sign)| sign).sum();
+            assert_eq!(total, square.winding_number(&origin));
+        }
+        assert!(square
+            .ray_crossings(Vec2::new(5.0, 0.0), Vec2::Y)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_as_generator_product() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]);
+        assert!(loop_with_word("", &puncture_points)
+            .as_generator_product()
+            .is_empty());
+        let path_type = loop_with_word("aabAA", &puncture_points);
+        assert_eq!(path_type.word_as_str(), "aabAA");
+        assert_eq!(
+            path_type.as_generator_product(),
+            vec![('A', 2), ('B', 1), ('A', -2)]
+        );
+    }
+
+    #[test]
+    fn test_puncture_points_handle() {
+        let handle = PuncturePointsHandle::new(PuncturePoint::auto_named([Vec2::ZERO]));
+        let mut path_types: Vec<PathType> = (0..3)
+            .map(|_| {
+                let mut path_type = PathType::from_handle(Vec2::new(-1.0, 1.0), &handle);
+                path_type.current_path = PLPath::new([
+                    Vec2::new(-1.0, 1.0),
+                    Vec2::new(6.0, 1.0),
+                    Vec2::new(6.0, -1.0),
+                    Vec2::new(-1.0, -1.0),
+                ]);
+                path_type.dirty = true;
+                path_type.update_word();
+                path_type
+            })
+            .collect();
+        assert!(path_types.iter().all(|p| p.word_as_str() == "a"));
+        assert!(!path_types[0].has_stale_punctures());
+
+        handle.update(|puncture_points| {
+            puncture_points.push(PuncturePoint::new(Vec2::new(5.0, 0.0), 'B'));
+        });
+        assert_eq!(handle.version(), 1);
+        assert!(path_types.iter().all(PathType::has_stale_punctures));
+        for path_type in &mut path_types {
+            assert!(path_type.sync_punctures());
+            assert!(!path_type.sync_punctures());
+        }
+        assert!(path_types.iter().all(|p| p.word_as_str() == "ab"));
+        let (_, shared) = handle.snapshot();
+        assert!(path_types
+            .iter()
+            .all(|p| Arc::ptr_eq(&p.puncture_points, &shared)));
+    }
+
+    #[test]
+    fn test_insert_puncture_into() {
+        let puncture_points: Arc<[PuncturePoint]> = PuncturePoint::auto_named([Vec2::ZERO]).into();
+        let square_at = |center: Vec2| {
+            PLPath::new([
+                center + Vec2::new(-1.0, 1.0),
+                center + Vec2::new(1.0, 1.0),
+                center + Vec2::new(1.0, -1.0),
+                center + Vec2::new(-1.0, -1.0),
+            ])
+        };
+        let mut path_types: Vec<(&str, PathType)> = [
+            ("home", Vec2::ZERO),
+            ("left", Vec2::new(-5.0, 0.0)),
+            ("right", Vec2::new(5.0, 0.0)),
+        ]
+        .into_iter()
+        .map(|(name, center)| {
+            (
+                name,
+                PathType::from_path(square_at(center), Arc::clone(&puncture_points)),
+            )
+        })
+        .collect();
+        let words = |path_types: &[(&str, PathType)]| -> Vec<String> {
+            path_types
+                .iter()
+                .map(|(_, path_type)| path_type.word())
+                .collect()
+        };
+        assert_eq!(words(&path_types), ["a", "", ""]);
+
+        let changed = insert_puncture_into(
+            &PuncturePoint::new(Vec2::new(5.0, 0.5), 'B'),
+            path_types
+                .iter_mut()
+                .map(|(name, path_type)| (*name, path_type)),
+        );
+        assert_eq!(changed, Ok(vec!["right"]));
+        assert_eq!(words(&path_types), ["a", "", "b"]);
+        assert!(path_types
+            .iter()
+            .all(|(_, path_type)| path_type.puncture_points.len() == 2 && path_type.validate()));
+        // The paths still share one allocation of puncture points.
+        assert!(path_types.iter().all(|(_, path_type)| Arc::ptr_eq(
+            &path_type.puncture_points,
+            &path_types[0].1.puncture_points
+        )));
+
+        let (_, home) = &mut path_types[0];
+        assert_eq!(
+            home.insert_puncture(PuncturePoint::new(Vec2::new(0.0, 5.0), 'C')),
+            Ok(false)
+        );
+        assert_eq!(
+            home.insert_puncture(PuncturePoint::new(Vec2::new(-0.5, 0.0), 'D')),
+            Ok(true)
+        );
+        assert_eq!(home.word_as_str(), "da");
+    }
+
+    #[test]
+    fn test_insert_puncture_rejects_duplicate_labels() {
+        let puncture_points: Arc<[PuncturePoint]> = PuncturePoint::auto_named([Vec2::ZERO]).into();
+        let square = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ]);
+        let mut first = PathType::from_path(square.clone(), Arc::clone(&puncture_points));
+        let mut second = PathType::from_path(square, puncture_points);
+        assert!(second
+            .insert_puncture(PuncturePoint::new(Vec2::new(0.0, 0.5), 'B'))
+            .is_ok_and(|changed| changed));
+
+        // `B` is new to `first`, but not to `second`, so neither path changes.
+        let duplicate = PuncturePoint::new(Vec2::new(5.0, 0.0), 'B');
+        assert_eq!(
+            insert_puncture_into(&duplicate, [(0, &mut first), (1, &mut second)]),
+            Err(DuplicateLabel)
+        );
+        assert_eq!(first.puncture_points.len(), 1);
+        assert_eq!(second.puncture_points.len(), 2);
+        assert_eq!(second.word_as_str(), "ab");
+        assert_eq!(
+            first.insert_puncture(PuncturePoint::new(Vec2::new(5.0, 0.0), 'A')),
+            Err(DuplicateLabel)
+        );
+        assert_eq!(first.word_as_str(), "a");
+    }
+
+    #[test]
+    fn test_restricted_word() {
+        let puncture_points =
+            PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(6.0, 0.0)]);
+        let commutator = loop_with_word("abAB", &puncture_points);
+        assert_eq!(commutator.word_as_str(), "abAB");
+        assert_eq!(commutator.restricted_word(&['A']), "");
+        assert_eq!(commutator.restricted_word(&['A', 'B']), "abAB");
+        let path_type = loop_with_word("abcaB", &puncture_points);
+        assert_eq!(path_type.word_as_str(), "abcaB");
+        assert_eq!(path_type.restricted_word(&['A', 'C']), "aca");
+        assert_eq!(path_type.restricted_word(&[]), "");
+    }
+
+    #[test]
+    fn test_render_lines() {
+        let path = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ]);
+        assert_eq!(
+            path.render_lines(),
+            [
+                (Vec2::new(-1.0, 1.0), Vec2::new(1.0, 1.0)),
+                (Vec2::new(1.0, 1.0), Vec2::new(1.0, -1.0)),
+                (Vec2::new(1.0, -1.0), Vec2::new(-1.0, -1.0)),
+                (Vec2::new(-1.0, -1.0), Vec2::new(-1.0, 1.0)),
+            ]
+        );
+        assert!(PLPath::new([Vec2::ZERO]).render_lines().is_empty());
+    }
+
+    #[cfg(feature = "bevy")]
+    #[test]
+    fn test_render_primitives() {
+        let path = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ]);
+        let primitives = path.render_primitives();
+        assert_eq!(primitives.len(), 4);
+        let (segment, center) = primitives[0];
+        assert_eq!(center, Vec2::new(0.0, 1.0));
+        assert_eq!(center + segment.point2(), Vec2::new(1.0, 1.0));
+        assert!(PLPath::new([Vec2::ZERO]).render_primitives().is_empty());
+    }
+
+    #[test]
+    fn test_orientation_convention() {
+        let puncture_points: Arc<[PuncturePoint]> =
+            PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]).into();
+        let path = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(4.0, -1.0),
+            Vec2::new(4.0, 1.0),
+        ]);
+        let clockwise = PathType::from_path(path.clone(), puncture_points.clone());
+        let counterclockwise = PathType::from_path(path, puncture_points)
+            .with_orientation(OrientationConvention::CounterclockwisePositive);
+        assert_eq!(
+            clockwise.orientation(),
+            OrientationConvention::ClockwisePositive
+        );
+        assert_eq!(clockwise.word_as_str(), "aaBA");
+        assert_eq!(counterclockwise.word_as_str(), "AAba");
+        assert_eq!(clockwise.letters(), counterclockwise.letters());
+    }
+
+    #[test]
+    fn test_commutes_with() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]);
+        let with_word = |word: &str| loop_with_word(word, &puncture_points);
+        let a = with_word("a");
+        let ab = with_word("ab");
+        assert_eq!(ab.word_as_str(), "ab");
+
+        // A loop commutes with its own powers and inverse.
+        let ab_squared = &ab * &ab;
+        assert_eq!(ab_squared.word_as_str(), "abab");
+        assert!(ab.commutes_with(&ab_squared));
+        assert!(ab.commutes_with(&-&ab));
+        assert!(a.commutes_with(&(&a * &a)));
+        assert!(ab.commutes_with(&with_word("")));
+        assert!(!a.commutes_with(&with_word("b")));
+        assert!(!ab.commutes_with(&with_word("ba")));
+    }
+
+    #[test]
+    fn test_simplify_hausdorff() {
+        let path = PLPath::new(
+            (0..=40)
+                .map(|i| {
+                    let x = i as f32 * 0.25;
+                    Vec2::new(x, x.sin())
+                })
+                .collect::<Vec<_>>(),
+        );
+        for max_error in [0.01, 0.1, 0.5] {
+            let simplified = path.simplify_hausdorff(max_error);
+            assert!(simplified.nodes.len() < path.nodes.len());
+            assert!(path.hausdorff_distance(&simplified) <= max_error);
+            assert_eq!(simplified.nodes.first(), path.nodes.first());
+            assert_eq!(simplified.nodes.last(), path.nodes.last());
+        }
+        assert_eq!(path.hausdorff_distance(&path), 0.0);
+        assert_eq!(path.simplify_hausdorff(0.0), path);
+    }
+
+    #[test]
+    fn test_from_parts() {
+        let nodes = vec![
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ];
+        let punctures = vec![(Vec2::ZERO, 'a'), (Vec2::new(5.0, 0.0), 'b')];
+        let manual = PathType::from_path(
+            PLPath::new(nodes.clone()),
+            punctures
+                .iter()
+                .map(|(position, name)| PuncturePoint::new(*position, *name))
+                .collect::<Vec<_>>()
+                .into(),
+        );
+        let path_type = PathType::from_parts(nodes, punctures);
+        assert_eq!(path_type.word_as_str(), "a");
+        assert_eq!(path_type.word(), manual.word());
+        assert_eq!(path_type.snapshot(), manual.snapshot());
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate puncture name A")]
+    fn test_from_parts_duplicate_names() {
+        PathType::from_parts(vec![Vec2::ZERO], vec![(Vec2::ZERO, 'a'), (Vec2::X, 'A')]);
+    }
+
+    #[test]
+    fn test_enclosed_area() {
+        let square = [Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y];
+        let counterclockwise = PathType::<char>::from_path(PLPath::new(square), Vec::new().into());
+        assert_eq!(counterclockwise.enclosed_area(), 1.0);
+        let clockwise = -&counterclockwise;
+        assert_eq!(clockwise.enclosed_area(), -1.0);
+        let point = PathType::<char>::new(Vec2::ONE, Vec::new());
+        assert_eq!(point.enclosed_area(), 0.0);
+    }
+
+    #[test]
+    fn test_word_at_prefix() {
+        let puncture_points: Arc<[PuncturePoint]> =
+            PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]).into();
+        let path = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(4.0, -1.0),
+            Vec2::new(4.0, 1.0),
+            Vec2::new(2.0, 1.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points);
+        let len = path_type.current_path.nodes.len();
+        assert_eq!(path_type.word_at_prefix(len), path_type.word());
+        assert_eq!(path_type.word_at_prefix(len + 3), path_type.word());
+        assert_eq!(path_type.word_at_prefix(0), "");
+        assert_eq!(path_type.word_at_prefix(2), "");
+        assert_eq!(path_type.word_at_prefix(3), "a");
+        assert_eq!(path_type.word_at_prefix(5), "aBA");
+    }
+
+    #[test]
+    fn test_self_intersection_count() {
+        let figure_eight = PLPath::new([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(0.0, 2.0),
+        ]);
+        assert_eq!(
+            figure_eight.self_intersections(Openness::Loop),
+            vec![Vec2::ONE]
+        );
+        assert_eq!(
+            figure_eight.self_intersections(Openness::Open),
+            vec![Vec2::ONE]
+        );
+
+        let puncture_points = PuncturePoint::auto_named([Vec2::new(0.5, 1.0)]).into();
+        let path_type = PathType::from_path(figure_eight, puncture_points);
+        assert_eq!(path_type.self_intersection_count(), 1);
+        assert!(path_type
+            .diagnose()
+            .contains(&Diagnostic::SelfIntersecting(1)));
+
+        let square = PLPath::closed([Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y]);
+        assert!(square.self_intersections(Openness::Open).is_empty());
+        assert!(square.self_intersections(Openness::Loop).is_empty());
+    }
+
+    #[test]
+    fn test_generator_loop() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.5)]);
+        let basepoint = Vec2::new(-4.0, -2.0);
+        for puncture in &puncture_points {
+            let generator = PathType::generator_loop(puncture, 0.5, basepoint);
+            assert_eq!(generator.start(), &basepoint);
+            assert_eq!(generator.end(), &basepoint);
+            let word =
+                |path: PLPath| PathType::from_path(path, puncture_points.clone().into()).word();
+            let name = puncture.name();
+            assert_eq!(
+                word(generator.clone()),
+                name.to_ascii_lowercase().to_string()
+            );
+            assert_eq!(word(generator.reverse()), name.to_string());
+        }
+    }
+
+    #[test]
+    fn test_relevant_punctures() {
+        let puncture_points = PuncturePoint::auto_named([
+            Vec2::ZERO,
+            Vec2::new(0.0, 5.0),
+            Vec2::new(1.5, 0.0),
+            Vec2::new(5.0, 5.0),
+        ]);
+        let path = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 0.5),
+            Vec2::new(-1.0, 0.5),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points.into());
+        let names = |relevance| {
+            path_type
+                .relevant_punctures(relevance)
+                .iter()
+                .map(PuncturePoint::name)
+                .collect::<String>()
+        };
+        assert_eq!(names(Relevance::Winding), "C");
+        assert_eq!(names(Relevance::BoundingBox), "AC");
+        // The word is `acA`: the loop goes over `A` to wind around `C`.
+        assert_eq!(path_type.word_as_str(), "acA");
+        assert_eq!(names(Relevance::Word), "AC");
+    }
+
+    #[test]
+    fn test_reflect() {
+        let square = PLPath::closed(vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ]);
+        assert!((square.signed_area() - 4.0).abs() < 1e-6);
+
+        let mirrored_x = square.reflect_x(1.0);
+        assert!((mirrored_x.signed_area() + 4.0).abs() < 1e-6);
+        assert_eq!(mirrored_x.nodes[1], Vec2::new(0.0, 0.0));
+        let mirrored_y = square.reflect_y(1.0);
+        assert!((mirrored_y.signed_area() + 4.0).abs() < 1e-6);
+        assert_eq!(mirrored_y.nodes[0], Vec2::new(0.0, 2.0));
+
+        let center = Vec2::new(1.0, 1.0);
+        assert_eq!(square.winding_number(&center), -1);
+        assert_eq!(mirrored_x.winding_number(&center), 1);
+    }
+
+    #[test]
+    fn test_integer_path_exact_word() {
+        let punctures = [('a', IVec2::new(0, 0)), ('b', IVec2::new(2, 0))];
+        // Starts level with the puncture points, where a float path would graze them.
+        let around_a = PLPathI::new(vec![
+            IVec2::new(-1, 0),
+            IVec2::new(-1, 1),
+            IVec2::new(1, 1),
+            IVec2::new(1, -1),
+            IVec2::new(-1, -1),
+        ]);
+        assert_eq!(around_a.word(&punctures), "a");
+        assert_eq!(around_a.winding_number(IVec2::ZERO), 1);
+        assert_eq!(around_a.winding_number(IVec2::new(2, 0)), 0);
+
+        let around_both = PLPathI::new(vec![
+            IVec2::new(-1, 0),
+            IVec2::new(-1, 1),
+            IVec2::new(3, 1),
+            IVec2::new(3, -1),
+            IVec2::new(-1, -1),
+        ]);
+        assert_eq!(around_both.word(&punctures), "ab");
+
+        // Passing exactly through a puncture point does not count as crossing over it.
+        let through = PLPathI::new(vec![IVec2::new(-1, 0), IVec2::new(3, 0)]);
+        assert_eq!(through.word(&punctures), "");
+
+        let float_path = PLPath::from(&around_a);
+        assert_eq!(float_path.winding_number(&Vec2::new(0.0, 0.0)), 1);
+        assert_eq!(PLPathI::from_path_rounded(&float_path), around_a);
+    }
+
+    #[test]
+    fn test_words_along() {
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::ZERO, 'A'),
+            PuncturePoint::new(Vec2::new(3.0, 0.0), 'B'),
+        ];
+        let positions = [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(4.0, 1.0),
+            Vec2::new(4.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(-1.0, 1.0),
+        ];
+        let start = Vec2::new(-1.0, 1.0);
+        let words: Vec<String> = words_along(positions, start, puncture_points.clone()).collect();
+        assert_eq!(words.len(), positions.len());
+        // Closed back to its start, a single segment goes over `A` and straight back.
+        assert_eq!(words[0], "");
+
+        let mut nodes = vec![start];
+        nodes.extend(positions);
+        let one_shot = PathType::from_path(PLPath::new(nodes), puncture_points.into());
+        assert_eq!(
+            words.last().map(String::as_str),
+            Some(one_shot.word_as_str())
+        );
+        assert_eq!(one_shot.word_as_str(), "ab");
+    }
+
+    #[test]
+    fn test_puncture_avoidance() {
+        let puncture_points =
+            vec![PuncturePoint::new(Vec2::ZERO, 'A')
+                .with_shape(PunctureShape::Circle { radius: 1.0 })];
+        let mut path_type = PathType::new(Vec2::new(-3.0, 0.0), puncture_points.clone())
+            .with_puncture_avoidance(true);
+        path_type.push(&Vec2::ZERO);
+        let end = *path_type.current_path.end();
+        assert!(end.length() > 1.0);
+        assert!(end.x > 1.0 && end.x < 1.01 && end.y.abs() < 1e-6);
+
+        // With no incoming direction, the point is moved straight up.
+        let mut from_center =
+            PathType::new(Vec2::ZERO, puncture_points.clone()).with_puncture_avoidance(true);
+        from_center.push(&Vec2::ZERO);
+        assert!(from_center.current_path.end().y > 1.0);
+
+        let mut unavoided = PathType::new(Vec2::new(-3.0, 0.0), puncture_points);
+        unavoided.push(&Vec2::ZERO);
+        assert_eq!(*unavoided.current_path.end(), Vec2::ZERO);
+
+        let rect = PunctureShape::Rect {
+            half_size: Vec2::new(2.0, 1.0),
+        };
+        assert_eq!(
+            rect.exit_along(Vec2::ZERO, Vec2::new(1.0, 0.5), Vec2::Y),
+            Some(Vec2::new(1.0, 1.0))
+        );
+        let ellipse = PunctureShape::Ellipse {
+            half_axes: Vec2::new(2.0, 1.0),
+        };
+        assert_eq!(
+            ellipse.exit_along(Vec2::ZERO, Vec2::ZERO, Vec2::X),
+            Some(Vec2::new(2.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn test_word_with_order() {
+        // `B` is directly below `A`, so the top segment passes over both at the same position along it.
+        let path_type = PathType::from_parts(
+            vec![
+                Vec2::new(-1.0, 1.0),
+                Vec2::new(1.0, 1.0),
+                Vec2::new(1.0, -2.0),
+                Vec2::new(-1.0, -2.0),
+            ],
+            vec![(Vec2::ZERO, 'A'), (Vec2::new(0.0, -1.0), 'B')],
+        );
+        assert_eq!(path_type.word_as_str(), "ab");
+        assert_eq!(path_type.word_with_order(&['a', 'b'], false), "ab");
+        assert_eq!(path_type.word_with_order(&['b', 'a'], false), "ba");
+        assert_eq!(path_type.word_with_order(&['B'], false), "ba");
+        assert_eq!(path_type.word_with_order(&[], true), "a b");
+
+        let reversed = -&path_type;
+        assert_eq!(reversed.word_with_order(&['a', 'b'], true), "a^-1 b^-1");
+        assert_eq!(reversed.word_with_order(&['b', 'a'], true), "b^-1 a^-1");
+        assert_eq!(reversed.word_with_order(&['b', 'a'], false), "BA");
+    }
+
+    #[test]
+    fn test_hazard_weighted_length() {
+        let puncture_points: Arc<[PuncturePoint]> =
+            vec![PuncturePoint::new(Vec2::ZERO, 'A')].into();
+        let hugging = PathType::from_path(
+            PLPath::new(vec![Vec2::new(-2.0, 0.1), Vec2::new(2.0, 0.1)]),
+            puncture_points.clone(),
+        );
+        let distant = PathType::from_path(
+            PLPath::new(vec![Vec2::new(-2.0, 10.0), Vec2::new(2.0, 10.0)]),
+            puncture_points,
+        );
+        let (near, far) = (
+            hugging.hazard_weighted_length(1.0),
+            distant.hazard_weighted_length(1.0),
+        );
+        assert!((near / 4.0 - (-0.1_f32).exp()).abs() < 1e-5);
+        assert!(near > far);
+        assert!(far < 1e-3);
+
+        let no_punctures = PathType::from_path(
+            PLPath::new(vec![Vec2::ZERO, Vec2::X]),
+            Vec::<PuncturePoint>::new().into(),
+        );
+        assert_eq!(no_punctures.hazard_weighted_length(1.0), 0.0);
+    }
+
+    #[test]
+    fn test_decompose_loops() {
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::ZERO, 'A'),
+            PuncturePoint::new(Vec2::new(3.0, 0.0), 'B'),
+        ];
+        let path = PLPath::new(vec![
+            Vec2::new(-1.0, 1.0),
+            // Around `A`, coming back just below the start.
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+            Vec2::new(-1.0, 0.9),
+            // Around `B`, passing under `A` both ways.
+            Vec2::new(-1.0, -2.0),
+            Vec2::new(2.0, -2.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(4.0, 1.0),
+            Vec2::new(4.0, -2.0),
+            Vec2::new(-1.0, -2.0),
+            Vec2::new(-1.0, 1.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points.into());
+        let loops = path_type.decompose_loops(0.2);
+        assert_eq!(loops, ["a", "b"]);
+        assert_eq!(
+            loops
+                .iter()
+                .fold(String::new(), |word, sub_loop| crate::word::reduce_concat(
+                    &word, sub_loop
+                )),
+            path_type.word()
+        );
+        assert_eq!(path_type.decompose_loops(0.05), ["ab"]);
+    }
+
+    #[test]
+    fn test_quotient_word() {
+        let puncture_points =
+            PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(6.0, 0.0)]);
+        let commutator = loop_with_word("abAB", &puncture_points);
+        assert_eq!(commutator.word_as_str(), "abAB");
+        assert_eq!(commutator.quotient_word(&['B']), "");
+        assert_eq!(commutator.quotient_word(&[]), "abAB");
+        let path_type = loop_with_word("abcaB", &puncture_points);
+        assert_eq!(path_type.word_as_str(), "abcaB");
+        assert_eq!(path_type.quotient_word(&['B']), "aca");
+        assert_eq!(path_type.quotient_word(&['A', 'C']), "");
+        assert_eq!(
+            path_type.quotient_word(&['B']),
+            path_type.restricted_word(&['A', 'C'])
+        );
+    }
+
+    #[test]
+    fn test_leaves_box() {
+        let (min, max) = (Vec2::splat(-2.0), Vec2::splat(2.0));
+        let inside = PLPath::new(vec![Vec2::ZERO, Vec2::new(2.0, 1.0), Vec2::new(-1.0, -2.0)]);
+        assert!(!inside.leaves_box(min, max));
+        assert_eq!(inside.first_exit_box(min, max), None);
+
+        let outside = PLPath::new(vec![
+            Vec2::ZERO,
+            Vec2::new(1.0, 1.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(1.0, -3.0),
+        ]);
+        assert!(outside.leaves_box(min, max));
+        assert_eq!(outside.first_exit_box(min, max), Some(2));
+    }
+
+    #[cfg(feature = "bevy")]
+    #[test]
+    fn test_leaves_rect() {
+        let rect = Rect::new(-2.0, -2.0, 2.0, 2.0);
+        let inside = PLPath::new(vec![Vec2::ZERO, Vec2::new(2.0, 1.0), Vec2::new(-1.0, -2.0)]);
+        assert!(!inside.leaves_rect(rect));
+        assert_eq!(inside.first_exit(rect), None);
+
+        let outside = PLPath::new(vec![
+            Vec2::ZERO,
+            Vec2::new(1.0, 1.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(1.0, -3.0),
+        ]);
+        assert!(outside.leaves_rect(rect));
+        assert_eq!(outside.first_exit(rect), Some(2));
+    }
+
+    #[test]
+    fn test_lerp_path() {
+        let a = PLPath::new(vec![Vec2::ZERO, Vec2::new(2.0, 0.0)]);
+        let b = PLPath::new(vec![Vec2::ZERO, Vec2::new(0.0, 1.0), Vec2::new(0.0, 2.0)]);
+        assert_eq!(
+            a.resample(3).nodes,
+            vec![Vec2::ZERO, Vec2::X, Vec2::new(2.0, 0.0)]
+        );
+        assert_eq!(lerp_path(&a, &b, 0.0), a.resample(3));
+        assert_eq!(lerp_path(&a, &b, 1.0), b.resample(3));
+        assert_eq!(
+            lerp_path(&a, &b, 0.5).nodes,
+            vec![Vec2::ZERO, Vec2::new(0.5, 0.5), Vec2::ONE]
+        );
+
+        let bent = PLPath::new(vec![Vec2::ZERO, Vec2::new(3.0, 0.0), Vec2::new(3.0, 1.0)]);
+        assert_eq!(
+            bent.resample(5).nodes,
+            vec![
+                Vec2::ZERO,
+                Vec2::X,
+                Vec2::new(2.0, 0.0),
+                Vec2::new(3.0, 0.0),
+                Vec2::new(3.0, 1.0),
+            ]
+        );
+        assert_eq!(bent.resample(1).nodes, vec![Vec2::ZERO]);
+        assert!(PLPath::new(vec![]).resample(3).nodes.is_empty());
+    }
+
+    #[test]
+    fn test_covered_cells() {
+        let cells = |path: &PLPath, cell_size: Vec2, expected: &[(i32, i32)]| {
+            let expected: HashSet<IVec2> =
+                expected.iter().map(|&(x, y)| IVec2::new(x, y)).collect();
+            assert_eq!(path.covered_cells(cell_size), expected);
+        };
+        // Through the corners of the cells on the diagonal.
+        let diagonal = PLPath::new(vec![Vec2::new(0.5, 0.5), Vec2::new(2.5, 2.5)]);
+        cells(
+            &diagonal,
+            Vec2::ONE,
+            &[(0, 0), (1, 0), (0, 1), (1, 1), (2, 1), (1, 2), (2, 2)],
+        );
+        cells(
+            &diagonal,
+            Vec2::splat(2.0),
+            &[(0, 0), (1, 0), (0, 1), (1, 1)],
+        );
+
+        let shallow = PLPath::new(vec![Vec2::new(0.2, 0.1), Vec2::new(2.2, 1.3)]);
+        cells(&shallow, Vec2::ONE, &[(0, 0), (1, 0), (1, 1), (2, 1)]);
+        let backwards = PLPath::new(vec![Vec2::new(2.2, 1.3), Vec2::new(0.2, 0.1)]);
+        cells(&backwards, Vec2::ONE, &[(0, 0), (1, 0), (1, 1), (2, 1)]);
+
+        let polyline = PLPath::new(vec![
+            Vec2::new(-0.5, 0.5),
+            Vec2::new(1.5, 0.5),
+            Vec2::new(1.5, -0.5),
+        ]);
+        cells(&polyline, Vec2::ONE, &[(-1, 0), (0, 0), (1, 0), (1, -1)]);
+        cells(&PLPath::new(vec![]), Vec2::ONE, &[]);
+    }
+
+    #[test]
+    fn test_path_type_with_node_data() {
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'A')];
+        let mut path = PathTypeWith::new(Vec2::new(-1.0, 1.0), "start", puncture_points);
+        path.push(&Vec2::new(1.0, 1.0), "corner");
+        assert_eq!(path.node_data(), ["start", "corner"]);
+
+        // The corner can be pulled tight, since the triangle it spans holds no puncture point.
+        path.push(&Vec2::new(1.0, 2.0), "up");
+        assert_eq!(path.node_data(), ["start", "up"]);
+
+        // This one cannot, as the triangle would sweep over `A`.
+        path.push(&Vec2::new(1.0, -2.0), "down");
+        let nodes_with_data: Vec<(Vec2, &str)> = path
+            .nodes_with_data()
+            .map(|(node, data)| (*node, *data))
+            .collect();
+        assert_eq!(
+            nodes_with_data,
+            [
+                (Vec2::new(-1.0, 1.0), "start"),
+                (Vec2::new(1.0, 2.0), "up"),
+                (Vec2::new(1.0, -2.0), "down"),
+            ]
+        );
+        assert_eq!(
+            path.path_type().current_path.nodes.len(),
+            path.node_data().len()
+        );
+        assert_eq!(path.into_inner().word_as_str(), "a");
+    }
+
+    #[test]
+    fn test_orientation() {
+        let square = PLPath::new(vec![Vec2::ZERO, Vec2::X, Vec2::ONE, Vec2::Y, Vec2::Y]);
+        assert_eq!(square.orientation(), Some(Orientation::CounterClockwise));
+        assert_eq!(square.reverse().orientation(), Some(Orientation::Clockwise));
+        assert!((square.total_turning() - std::f32::consts::TAU).abs() < 1e-5);
+
+        // A thin counterclockwise sliver far from the origin, with an area of 10, loses it all to rounding.
+        let offset = Vec2::splat(30_000.0);
+        let sliver = PLPath::new(vec![
+            offset,
+            offset + Vec2::new(200.0, 0.0),
+            offset + Vec2::new(100.0, 0.1),
+        ]);
+        assert!(sliver.signed_area().abs() < 1.0);
+        assert_eq!(sliver.orientation(), Some(Orientation::CounterClockwise));
+        assert_eq!(sliver.reverse().orientation(), Some(Orientation::Clockwise));
+
+        let doubled_back = PLPath::new(vec![Vec2::ZERO, Vec2::X, Vec2::new(2.0, 0.0)]);
+        assert_eq!(doubled_back.orientation(), Some(Orientation::Degenerate));
+        assert_eq!(PLPath::line(Vec2::ZERO, Vec2::X).orientation(), None);
+    }
+
+    #[cfg(feature = "bevy")]
+    #[test]
+    fn test_compute_words_async() {
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(3.0, 0.0)]);
+        let snapshots: Vec<PathSnapshot> = (0..20)
+            .map(|i| {
+                let mut path_type = PathType::new(Vec2::new(-1.0, 1.0), puncture_points.clone());
+                let right = if i % 2 == 0 { 1.0 } else { 4.0 };
+                for node in [
+                    Vec2::new(right, 1.0),
+                    Vec2::new(right, -1.0 - i as f32),
+                    Vec2::new(-1.0, -1.0),
+                ] {
+                    path_type.push(&node);
+                }
+                if i % 3 == 0 {
+                    path_type = -&path_type;
+                }
+                if i % 5 == 0 {
+                    path_type = path_type
+                        .with_orientation(OrientationConvention::CounterclockwisePositive)
+                        .with_openness(Openness::Open)
+                        .with_winding_epsilon(0.1);
+                }
+                path_type.snapshot()
+            })
+            .collect();
+        let words = bevy::tasks::block_on(compute_words_async(snapshots.clone()));
+        let expected: Vec<String> = snapshots
+            .iter()
+            .map(|snapshot| snapshot.word.clone())
+            .collect();
+        assert_eq!(words, expected);
+        assert!(words.iter().any(|word| word == "ab"));
+        assert!(words.iter().any(|word| word == "A"));
+        // Only the open path over both puncture points, written counterclockwise, has this word.
+        assert_eq!(words[5], "AB");
+    }
+
+    #[test]
+    fn test_resample_adaptive() {
+        let corner = Vec2::new(100.0, 0.0);
+        let path = PLPath::new(vec![Vec2::ZERO, corner, Vec2::new(100.0, 100.0)]);
+        let resampled = path.resample_adaptive(1.0, 10.0, 2.0);
+        assert!(resampled.nodes.contains(&corner));
+        assert_eq!(resampled.nodes.first(), Some(&Vec2::ZERO));
+        assert_eq!(resampled.nodes.last(), Some(&Vec2::new(100.0, 100.0)));
+        for pair in resampled.nodes.windows(2) {
+            assert!(pair[0].distance(pair[1]) <= 10.0 + 1e-3);
+        }
+
+        let nodes_near = |point: Vec2| {
+            resampled
+                .nodes
+                .iter()
+                .filter(|node| node.distance(point) <= 10.0)
+                .count()
+        };
+        let (at_corner, on_straight) = (nodes_near(corner), nodes_near(Vec2::new(50.0, 0.0)));
+        assert!(at_corner >= 3 * on_straight, "{at_corner} vs {on_straight}");
+        assert!(on_straight <= 3);
+
+        let straight = PLPath::new(vec![
+            Vec2::ZERO,
+            Vec2::new(50.0, 0.0),
+            Vec2::new(100.0, 0.0),
+        ]);
+        assert!(straight.resample_adaptive(1.0, 10.0, 2.0).nodes.len() <= 12);
+    }
+
+    #[test]
+    fn test_segment_letters_match_per_segment_loop() {
+        // The per-segment crossing test the blocked layout replaced, kept as the reference.
+        fn reference_winding(
+            position: &Vec2,
+            start: &Vec2,
+            end: &Vec2,
+            epsilon: f32,
+        ) -> Option<i32> {
+            let cross_product = (end.y - start.y).mul_add(
+                position.x - start.x,
+                -((position.y - start.y) * (end.x - start.x)),
+            );
+            let tolerance = epsilon * start.distance(*end);
+            if cross_product > tolerance && (start.x..end.x).contains(&position.x) {
+                return Some(1);
+            }
+            if cross_product < -tolerance && (end.x..start.x).contains(&position.x) {
+                return Some(-1);
+            }
+            None
+        }
+        // The letters of one segment, looping over the puncture points for each segment.
+        fn reference_letters(path_type: &PathType, start: Vec2, end: Vec2) -> Vec<(char, i32)> {
+            if start.x.partial_cmp(&end.x).is_none_or(Ordering::is_eq) {
+                return Vec::new();
+            }
+            let mut crossings: Vec<(f32, char, i32)> = path_type
+                .puncture_points
+                .iter()
+                .filter_map(|puncture| {
+                    let position = puncture.position();
+                    reference_winding(position, &start, &end, path_type.winding_epsilon)
+                        .map(|n| ((*position - start).dot(end - start), *puncture.label(), n))
+                })
+                .collect();
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+            crossings
+                .into_iter()
+                .map(|(_, name, n)| (name, n))
+                .collect()
+        }
+
+        // Deterministic scattered values in `[0, 1)`.
+        let noise = |i: usize| ((i as f32 * 12.9898).sin() * 43_758.547).rem_euclid(1.0);
+        let puncture_points = PuncturePoint::auto_named(
+            (0..24).map(|i| Vec2::new(noise(2 * i), noise(2 * i + 1)) * 100.0),
+        );
+        let nodes: Vec<Vec2> = (0..3000)
+            .map(|i| Vec2::new(noise(7 * i + 1000), noise(7 * i + 1001)) * 100.0)
+            .collect();
+        // A long random walk with unit steps, folded back into the field.
+        let walk: Vec<Vec2> = (0..20_000)
+            .scan(Vec2::splat(50.0), |position, i| {
+                let step = Vec2::from_angle(noise(i + 5000) * std::f32::consts::TAU);
+                *position = (*position + step).rem_euclid(Vec2::splat(100.0));
+                Some(*position)
+            })
+            .collect();
+        // Some puncture points straight under a horizontal segment, to exercise ties.
+        let mut nodes_with_ties = nodes.clone();
+        nodes_with_ties.extend([Vec2::new(-10.0, 200.0), Vec2::new(210.0, 200.0)]);
+        let mut stacked = puncture_points.clone();
+        stacked.extend([
+            PuncturePoint::new(Vec2::new(50.0, 150.0), 'Y'),
+            PuncturePoint::new(Vec2::new(50.0, 120.0), 'Z'),
+        ]);
+
+        for (nodes, puncture_points, wrap) in [
+            (nodes.clone(), puncture_points.clone(), Wrap::default()),
+            (walk, puncture_points.clone(), Wrap::default()),
+            (nodes_with_ties, stacked, Wrap::default()),
+            (
+                nodes,
+                puncture_points,
+                Wrap {
+                    x: Some(100.0),
+                    y: Some(100.0),
+                },
+            ),
+        ] {
+            let path_type = PathType::from_path(PLPath::new(nodes), puncture_points.into())
+                .with_wrap(wrap)
+                .with_winding_epsilon(0.01);
+            let reference: Vec<Vec<(char, i32)>> = path_type
+                .current_path
+                .segments(path_type.openness)
+                .map(|(start, end)| {
+                    path_type
+                        .travelled_segments(*start, *end)
+                        .flat_map(|(start, end)| reference_letters(&path_type, start, end))
+                        .collect()
+                })
+                .collect();
+            assert_eq!(path_type.segment_generators(), reference);
+            assert_eq!(
+                path_type.letters,
+                path_type.reduce_segment_letters(&reference)
+            );
+            assert!(!path_type.word_as_str().is_empty());
+        }
     }
 }
@@ -0,0 +1,1318 @@
+use super::{PLPath, PathType, PuncturePoint};
+use bevy::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+/// Adds systems for updating the path timer and updating the position of entities along the path.
+pub struct PathPlugin;
+
+impl Plugin for PathPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                tick_path_timer,
+                sync_shared_punctures
+                    .before(update_entity_position)
+                    .before(update_entity_position_camera_space),
+                update_entity_position,
+                update_entity_position_camera_space,
+                update_words_on_timer
+                    .after(update_entity_position)
+                    .after(update_entity_position_camera_space),
+                close_loops
+                    .after(update_entity_position)
+                    .after(update_entity_position_camera_space),
+            )
+                .run_if(path_tracking_enabled),
+        )
+        .add_event::<LoopClosed>()
+        .insert_resource(PathTimer::default())
+        .init_resource::<PathTrackingEnabled>()
+        .init_resource::<PathMovementEpsilon>();
+        #[cfg(feature = "serialize")]
+        app.register_type::<PathType>();
+    }
+}
+
+/// Plugin for debugging paths.
+/// Adds a system for rendering paths to the screen using Bevy's 2d primitives.
+///
+/// `PathDebugPlugin::default()` renders with the default [`PathDebugConfig`];
+/// use [`PathDebugPlugin::with_config`] to customize the rendering.
+#[derive(Debug, Clone, Default)]
+pub struct PathDebugPlugin {
+    config: PathDebugConfig,
+}
+
+impl PathDebugPlugin {
+    /// A debug plugin that inserts `config` as the initial [`PathDebugConfig`] resource.
+    pub const fn with_config(config: PathDebugConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl Plugin for PathDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .init_gizmo_group::<PathGizmos>()
+            .add_systems(
+                Update,
+                (
+                    apply_path_debug_config.run_if(resource_changed::<PathDebugConfig>),
+                    (
+                        debug_render_paths,
+                        update_word_labels,
+                        update_segment_labels,
+                    )
+                        .run_if(path_tracking_enabled),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Rendering options for [`PathDebugPlugin`].
+#[derive(Debug, Clone, PartialEq, Resource)]
+pub struct PathDebugConfig {
+    /// Width of the rendered path lines, in pixels.
+    pub line_width: f32,
+    /// If set, each segment is drawn dashed, with dashes and gaps of this length.
+    pub dash_length: Option<f32>,
+    /// Depth at which the path lines are drawn.
+    ///
+    /// Bevy draws gizmos on top of all 2D content seen by a `Camera2d`, so this only changes
+    /// the layering for cameras that depth-test gizmos, such as 3D cameras looking at the plane.
+    pub z: f32,
+    /// Whether to render each path's word as a label following the end of the path.
+    pub show_word_label: bool,
+    /// Whether to label each segment of a path with the letters it contributes to the word,
+    /// as given by [`PathType::segment_generators`]. Segments contributing nothing are not labelled.
+    pub show_segment_labels: bool,
+    /// If set, the branch cuts of the puncture points inside this viewport are drawn.
+    ///
+    /// The branch cut of a puncture point is the vertical ray going up from it: a path
+    /// changes its word exactly when it crosses one.
+    pub branch_cuts: Option<Rect>,
+}
+
+impl Default for PathDebugConfig {
+    fn default() -> Self {
+        Self {
+            line_width: 2.0,
+            dash_length: None,
+            z: 0.0,
+            show_word_label: false,
+            show_segment_labels: false,
+            branch_cuts: None,
+        }
+    }
+}
+
+impl PathDebugConfig {
+    /// Starts building a config from the defaults.
+    ///
+    /// ```
+    /// use charred_path::piecewise_linear::PathDebugConfig;
+    ///
+    /// let config = PathDebugConfig::builder()
+    ///     .line_width(4.0)
+    ///     .dash_length(8.0)
+    ///     .show_word_label(true)
+    ///     .build();
+    /// assert_eq!(config.dash_length, Some(8.0));
+    /// ```
+    pub fn builder() -> PathDebugConfigBuilder {
+        PathDebugConfigBuilder::default()
+    }
+}
+
+/// Fluent builder for [`PathDebugConfig`], see [`PathDebugConfig::builder`].
+#[derive(Debug, Clone, Default)]
+pub struct PathDebugConfigBuilder {
+    config: PathDebugConfig,
+}
+
+impl PathDebugConfigBuilder {
+    /// Sets [`PathDebugConfig::line_width`].
+    #[must_use]
+    pub const fn line_width(mut self, line_width: f32) -> Self {
+        self.config.line_width = line_width;
+        self
+    }
+
+    /// Sets [`PathDebugConfig::dash_length`], drawing paths dashed.
+    #[must_use]
+    pub const fn dash_length(mut self, dash_length: f32) -> Self {
+        self.config.dash_length = Some(dash_length);
+        self
+    }
+
+    /// Sets [`PathDebugConfig::z`].
+    #[must_use]
+    pub const fn z(mut self, z: f32) -> Self {
+        self.config.z = z;
+        self
+    }
+
+    /// Sets [`PathDebugConfig::show_word_label`].
+    #[must_use]
+    pub const fn show_word_label(mut self, show_word_label: bool) -> Self {
+        self.config.show_word_label = show_word_label;
+        self
+    }
+
+    /// Sets [`PathDebugConfig::show_segment_labels`].
+    #[must_use]
+    pub const fn show_segment_labels(mut self, show_segment_labels: bool) -> Self {
+        self.config.show_segment_labels = show_segment_labels;
+        self
+    }
+
+    /// Sets [`PathDebugConfig::branch_cuts`], drawing branch cuts inside `viewport`.
+    #[must_use]
+    pub const fn branch_cuts(mut self, viewport: Rect) -> Self {
+        self.config.branch_cuts = Some(viewport);
+        self
+    }
+
+    /// Finishes building the config.
+    pub const fn build(self) -> PathDebugConfig {
+        self.config
+    }
+}
+
+/// Gizmo config group used for rendering paths, so path line widths don't affect other gizmos.
+#[derive(Default, Reflect, GizmoConfigGroup)]
+pub struct PathGizmos;
+
+/// Copies the line width from `PathDebugConfig` into the path gizmo config.
+fn apply_path_debug_config(
+    config: Res<PathDebugConfig>,
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    config_store.config_mut::<PathGizmos>().0.line_width = config.line_width;
+}
+
+/// Resource for pausing and resuming all path tracking, e.g. during cutscenes.
+///
+/// While disabled, no nodes are recorded and paths are not rendered,
+/// but everything recorded so far is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Resource)]
+pub struct PathTrackingEnabled(pub bool);
+
+impl Default for PathTrackingEnabled {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Run condition for the path systems. Tracking is enabled unless `PathTrackingEnabled` says otherwise.
+fn path_tracking_enabled(enabled: Option<Res<PathTrackingEnabled>>) -> bool {
+    enabled.is_none_or(|enabled| enabled.0)
+}
+
+/// Resource struct representing a timer for path updates.
+#[derive(Resource)]
+pub struct PathTimer {
+    pub timer: Timer,
+}
+
+impl Default for PathTimer {
+    fn default() -> Self {
+        Self {
+            timer: Timer::new(Duration::from_millis(250), TimerMode::Repeating),
+        }
+    }
+}
+
+impl PathTimer {
+    /// The shortest interval recommended by [`PathTimer::recommended_sample_interval`].
+    pub const MIN_SAMPLE_INTERVAL: Duration = Duration::from_millis(10);
+    /// The longest interval recommended by [`PathTimer::recommended_sample_interval`].
+    pub const MAX_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Returns the timer duration that places nodes about `target_spacing` apart
+    /// for an entity moving at `recent_speed`, i.e. `target_spacing / recent_speed`.
+    ///
+    /// The result is clamped between [`PathTimer::MIN_SAMPLE_INTERVAL`] and [`PathTimer::MAX_SAMPLE_INTERVAL`],
+    /// and an entity that is not moving gets the longest interval.
+    pub fn recommended_sample_interval(target_spacing: f32, recent_speed: f32) -> Duration {
+        let seconds = target_spacing / recent_speed;
+        if !seconds.is_finite() || seconds <= 0.0 {
+            return Self::MAX_SAMPLE_INTERVAL;
+        }
+        Duration::from_secs_f32(seconds).clamp(Self::MIN_SAMPLE_INTERVAL, Self::MAX_SAMPLE_INTERVAL)
+    }
+}
+
+/// Updates the path timer.
+fn tick_path_timer(mut path_timer: ResMut<PathTimer>, time: Res<Time>) {
+    path_timer.timer.tick(time.delta());
+}
+
+/// Takes the latest puncture points of every path made with [`PathType::from_handle`] whose handle changed.
+fn sync_shared_punctures(mut path_types: Query<&mut PathType>) {
+    for mut path_type in path_types.iter_mut() {
+        if path_type.has_stale_punctures() {
+            path_type.sync_punctures();
+        }
+    }
+}
+
+/// Resource for throttling word computation independently of position sampling.
+///
+/// While this resource exists, tracked positions are still pushed every frame, but the words of the
+/// paths are only recomputed when this timer finishes. In between, [`PathType::word`] can lag behind the
+/// nodes by up to one interval; see [`PathType::is_word_stale`]. Without it, words are updated on every push.
+#[derive(Resource)]
+pub struct WordTimer {
+    pub timer: Timer,
+}
+
+impl Default for WordTimer {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(250))
+    }
+}
+
+impl WordTimer {
+    /// A word timer recomputing words every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            timer: Timer::new(interval, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Recomputes the stale words of all paths whenever the `WordTimer` finishes.
+fn update_words_on_timer(
+    word_timer: Option<ResMut<WordTimer>>,
+    time: Res<Time>,
+    mut path_types: Query<&mut PathType>,
+) {
+    let Some(mut word_timer) = word_timer else {
+        return;
+    };
+    if !word_timer.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    for mut path_type in path_types.iter_mut() {
+        if path_type.is_word_stale() {
+            path_type.update_word();
+        }
+    }
+}
+
+/// Component for closing the path of an entity into a loop when it comes back within `threshold` of
+/// the start of its path, after having been farther away.
+///
+/// Closing the loop sends a [`LoopClosed`] event with the word and restarts the path from the same start,
+/// see [`PathType::restart`], so the entity can trace a new loop.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct AutoCloseLoop {
+    pub threshold: f32,
+}
+
+/// Event sent when an entity with [`AutoCloseLoop`] closes its loop.
+#[derive(Debug, Clone, PartialEq, Eq, Event)]
+pub struct LoopClosed {
+    pub entity: Entity,
+    /// The word of the closed loop.
+    pub word: String,
+}
+
+/// Marker for `AutoCloseLoop` entities that have been farther than the threshold from the start of their path.
+#[derive(Component)]
+struct LeftLoopStart;
+
+/// Closes the loops of `AutoCloseLoop` entities that came back to the start of their path.
+fn close_loops(
+    mut commands: Commands,
+    mut path_query: Query<(Entity, &mut PathType, &AutoCloseLoop, Has<LeftLoopStart>)>,
+    mut loop_closed: EventWriter<LoopClosed>,
+) {
+    for (entity, mut path_type, auto_close, left_start) in path_query.iter_mut() {
+        let path = &path_type.current_path;
+        let near_start = path.start().distance(*path.end()) <= auto_close.threshold;
+        if !left_start {
+            if !near_start {
+                commands.entity(entity).insert(LeftLoopStart);
+            }
+            continue;
+        }
+        if near_start {
+            let word = path_type.update_word();
+            path_type.restart();
+            commands.entity(entity).remove::<LeftLoopStart>();
+            loop_closed.send(LoopClosed { entity, word });
+        }
+    }
+}
+
+/// Marker component for entities whose path is recorded in camera space rather than world space.
+///
+/// Useful when the entity stays put on screen while the world scrolls around it.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct CameraSpacePath;
+
+/// Snaps every recorded position of an entity to the nearest point of a grid.
+///
+/// This keeps the word stable against sub-pixel jitter and cuts down on spurious nodes.
+/// Note that coarse snapping can move a node across a puncture point, which changes the word.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct GridSnap {
+    pub cell: Vec2,
+}
+
+impl GridSnap {
+    /// Returns the grid point nearest to `point`.
+    pub fn snap(&self, point: Vec2) -> Vec2 {
+        (point / self.cell).round() * self.cell
+    }
+}
+
+/// Component for lifting the pen: while `Tracing(false)`, the positions of the entity are not recorded.
+///
+/// When tracing resumes, the next recorded position is joined to the last one by a straight segment,
+/// so the word treats the gap as a straight jump between where the pen was lifted and where it came down,
+/// whatever the entity did in between. Entities without this component are always traced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct Tracing(pub bool);
+
+impl Default for Tracing {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Resource for the distance an entity has to move from the end of its path before a new node is recorded.
+///
+/// The default of `0.0` records every change of position.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Resource)]
+pub struct PathMovementEpsilon(pub f32);
+
+/// Pushes `position` onto the path, after snapping, if it moved farther than `epsilon` from the end of the path.
+///
+/// With `defer_word`, the word is left for [`WordTimer`] to recompute.
+/// The path is only marked as changed if a node was pushed or `defer_word` changed,
+/// so `Changed<PathType>` filters skip entities standing still.
+fn track_position(
+    path_type: &mut Mut<PathType>,
+    position: Vec2,
+    grid_snap: Option<&GridSnap>,
+    epsilon: f32,
+    defer_word: bool,
+) {
+    let position = grid_snap.map_or(position, |grid_snap| grid_snap.snap(position));
+    if path_type.defer_word != defer_word {
+        path_type.defer_word = defer_word;
+    }
+    if path_type
+        .bypass_change_detection()
+        .push_if_moved(&position, epsilon)
+    {
+        path_type.set_changed();
+    }
+}
+
+/// The components read when recording the position of an entity.
+type TrackedPath = (
+    &'static mut PathType,
+    &'static Transform,
+    Option<&'static GridSnap>,
+    Option<&'static Tracing>,
+);
+
+/// Updates the position of entities along the path.
+fn update_entity_position(
+    mut path_query: Query<TrackedPath, Without<CameraSpacePath>>,
+    epsilon: Option<Res<PathMovementEpsilon>>,
+    word_timer: Option<Res<WordTimer>>,
+    // path_timer: Res<PathTimer>,
+) {
+    let epsilon = epsilon.map_or(0.0, |epsilon| epsilon.0);
+    let defer_word = word_timer.is_some();
+    // if path_timer.timer.just_finished() {
+    for (mut path_type, transform, grid_snap, tracing) in path_query.iter_mut() {
+        if tracing.is_some_and(|tracing| !tracing.0) {
+            continue;
+        }
+        track_position(
+            &mut path_type,
+            transform.translation.truncate(),
+            grid_snap,
+            epsilon,
+            defer_word,
+        );
+    }
+    // }
+}
+
+/// Updates the position of `CameraSpacePath` entities along the path, relative to the active camera.
+fn update_entity_position_camera_space(
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    mut path_query: Query<TrackedPath, With<CameraSpacePath>>,
+    epsilon: Option<Res<PathMovementEpsilon>>,
+    word_timer: Option<Res<WordTimer>>,
+) {
+    let Some((_, camera_transform)) = camera_query.iter().find(|(camera, _)| camera.is_active)
+    else {
+        return;
+    };
+    let view = camera_transform.compute_matrix().inverse();
+    let epsilon = epsilon.map_or(0.0, |epsilon| epsilon.0);
+    let defer_word = word_timer.is_some();
+    for (mut path_type, transform, grid_snap, tracing) in path_query.iter_mut() {
+        if tracing.is_some_and(|tracing| !tracing.0) {
+            continue;
+        }
+        let current_position = view.transform_point3(transform.translation).truncate();
+        track_position(
+            &mut path_type,
+            current_position,
+            grid_snap,
+            epsilon,
+            defer_word,
+        );
+    }
+}
+
+/// This visualizes the piecewise-linear paths.
+fn debug_render_paths(
+    path_types: Query<&PathType>,
+    config: Res<PathDebugConfig>,
+    mut gizmos: Gizmos<PathGizmos>,
+) {
+    let mut puncture_points: Vec<PuncturePoint> = Vec::new();
+    for path_type in path_types.iter() {
+        for (start, end) in debug_lines(&path_type.current_path, &config) {
+            gizmos.line(start, end, Color::WHITE);
+        }
+        for puncture in path_type.puncture_points.iter() {
+            if !puncture_points.contains(puncture) {
+                puncture_points.push(*puncture);
+            }
+        }
+    }
+    if let Some(viewport) = config.branch_cuts {
+        for (start, end) in branch_cut_lines(&puncture_points, viewport) {
+            gizmos.line(start.extend(config.z), end.extend(config.z), Color::GRAY);
+        }
+    }
+}
+
+/// The branch cuts of the puncture points inside `viewport`: vertical lines going up
+/// from each puncture point to the top of the viewport.
+fn branch_cut_lines(puncture_points: &[PuncturePoint], viewport: Rect) -> Vec<(Vec2, Vec2)> {
+    puncture_points
+        .iter()
+        .map(PuncturePoint::position)
+        .filter(|position| viewport.contains(**position))
+        .map(|position| (*position, Vec2::new(position.x, viewport.max.y)))
+        .collect()
+}
+
+/// The lines drawn by `debug_render_paths` for a path, at the configured depth and dashing.
+fn debug_lines(path: &PLPath, config: &PathDebugConfig) -> Vec<(Vec3, Vec3)> {
+    path.render_lines()
+        .into_iter()
+        .flat_map(|(start, end)| {
+            config.dash_length.map_or_else(
+                || vec![(start, end)],
+                |dash_length| dashes(start, end, dash_length),
+            )
+        })
+        .map(|(start, end)| (start.extend(config.z), end.extend(config.z)))
+        .collect()
+}
+
+/// Offset of a word label from the end of its path.
+const WORD_LABEL_OFFSET: Vec2 = Vec2::new(0.0, 20.0);
+
+/// A text label showing the word of the path on the `target` entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct PathWordLabel {
+    pub target: Entity,
+}
+
+/// Spawns, updates and despawns the word labels of paths, according to `PathDebugConfig::show_word_label`.
+fn update_word_labels(
+    mut commands: Commands,
+    config: Res<PathDebugConfig>,
+    path_types: Query<(Entity, &PathType)>,
+    mut labels: Query<(Entity, &PathWordLabel, &mut Text, &mut Transform)>,
+) {
+    let mut labelled = HashSet::new();
+    for (label_entity, label, mut text, mut transform) in labels.iter_mut() {
+        match path_types.get(label.target) {
+            Ok((_, path_type)) if config.show_word_label => {
+                if text.sections[0].value != path_type.word_as_str() {
+                    text.sections[0].value = path_type.word();
+                }
+                transform.translation =
+                    (*path_type.current_path.end() + WORD_LABEL_OFFSET).extend(config.z);
+                labelled.insert(label.target);
+            }
+            _ => commands.entity(label_entity).despawn(),
+        }
+    }
+    if !config.show_word_label {
+        return;
+    }
+    for (entity, path_type) in path_types.iter() {
+        if !labelled.contains(&entity) {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(path_type.word(), TextStyle::default()),
+                    transform: Transform::from_translation(
+                        (*path_type.current_path.end() + WORD_LABEL_OFFSET).extend(config.z),
+                    ),
+                    ..Default::default()
+                },
+                PathWordLabel { target: entity },
+            ));
+        }
+    }
+}
+
+/// A text label showing the letters contributed by segment `index` of the path on the `target` entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct PathSegmentLabel {
+    pub target: Entity,
+    pub index: usize,
+}
+
+/// The midpoint and word fragment of each segment of the path that contributes letters to its word,
+/// keyed by the segment's index in [`PathType::segment_generators`].
+fn segment_fragments(path_type: &PathType) -> HashMap<usize, (Vec2, String)> {
+    path_type
+        .current_path
+        .segments(path_type.openness)
+        .zip(path_type.segment_generators())
+        .enumerate()
+        .filter(|(_, (_, letters))| !letters.is_empty())
+        .map(|(index, ((start, end), letters))| {
+            (index, (start.lerp(*end, 0.5), path_type.render(letters)))
+        })
+        .collect()
+}
+
+/// Spawns, updates and despawns the segment labels of paths, according to `PathDebugConfig::show_segment_labels`.
+///
+/// While the word of a path is stale, see [`WordTimer`], its segment letters belong to older segments,
+/// so its labels are left as they are until the word is recomputed.
+fn update_segment_labels(
+    mut commands: Commands,
+    config: Res<PathDebugConfig>,
+    path_types: Query<(Entity, &PathType)>,
+    mut labels: Query<(Entity, &PathSegmentLabel, &mut Text, &mut Transform)>,
+) {
+    let stale: HashSet<Entity> = path_types
+        .iter()
+        .filter(|(_, path_type)| config.show_segment_labels && path_type.is_word_stale())
+        .map(|(entity, _)| entity)
+        .collect();
+    let mut fragments: HashMap<Entity, HashMap<usize, (Vec2, String)>> =
+        if config.show_segment_labels {
+            path_types
+                .iter()
+                .filter(|(entity, _)| !stale.contains(entity))
+                .map(|(entity, path_type)| (entity, segment_fragments(path_type)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+    for (label_entity, label, mut text, mut transform) in labels.iter_mut() {
+        if stale.contains(&label.target) {
+            continue;
+        }
+        match fragments
+            .get_mut(&label.target)
+            .and_then(|fragments| fragments.remove(&label.index))
+        {
+            Some((midpoint, fragment)) => {
+                if text.sections[0].value != fragment {
+                    text.sections[0].value = fragment;
+                }
+                transform.translation = midpoint.extend(config.z);
+            }
+            None => commands.entity(label_entity).despawn(),
+        }
+    }
+    for (target, fragments) in fragments {
+        for (index, (midpoint, fragment)) in fragments {
+            commands.spawn((
+                Text2dBundle {
+                    text: Text::from_section(fragment, TextStyle::default()),
+                    transform: Transform::from_translation(midpoint.extend(config.z)),
+                    ..Default::default()
+                },
+                PathSegmentLabel { target, index },
+            ));
+        }
+    }
+}
+
+/// Splits the line segment from `start` to `end` into dashes of length `dash_length`,
+/// separated by gaps of the same length. The last dash may be shorter.
+fn dashes(start: Vec2, end: Vec2, dash_length: f32) -> Vec<(Vec2, Vec2)> {
+    let length = start.distance(end);
+    if dash_length <= 0.0 || length <= dash_length {
+        return vec![(start, end)];
+    }
+    let direction = (end - start) / length;
+    let count = (length / (2.0 * dash_length)).ceil() as usize;
+    (0..count)
+        .map(|i| {
+            let from = 2.0 * dash_length * i as f32;
+            let to = (from + dash_length).min(length);
+            (start + direction * from, start + direction * to)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::piecewise_linear::{Openness, PuncturePoint, PuncturePointsHandle};
+
+    #[test]
+    fn test_dashes() {
+        let pieces = dashes(Vec2::ZERO, Vec2::new(10.0, 0.0), 1.0);
+        assert_eq!(pieces.len(), 5);
+        assert_eq!(pieces[1], (Vec2::new(2.0, 0.0), Vec2::new(3.0, 0.0)));
+
+        let pieces = dashes(Vec2::ZERO, Vec2::new(0.0, 9.0), 2.0);
+        assert_eq!(pieces.len(), 3);
+        assert_eq!(pieces[2], (Vec2::new(0.0, 8.0), Vec2::new(0.0, 9.0)));
+
+        assert_eq!(
+            dashes(Vec2::ZERO, Vec2::X, 2.0),
+            vec![(Vec2::ZERO, Vec2::X)]
+        );
+    }
+
+    #[test]
+    fn test_camera_space_path() {
+        let mut app = App::new();
+        app.add_systems(
+            Update,
+            (update_entity_position, update_entity_position_camera_space),
+        );
+        let camera = app
+            .world
+            .spawn((Camera::default(), GlobalTransform::default()))
+            .id();
+        let tracked = app
+            .world
+            .spawn((
+                Transform::from_xyz(5.0, 0.0, 0.0),
+                PathType::<char>::new(Vec2::new(5.0, 0.0), vec![]),
+                CameraSpacePath,
+            ))
+            .id();
+
+        app.update();
+        *app.world
+            .entity_mut(camera)
+            .get_mut::<GlobalTransform>()
+            .expect("camera has a GlobalTransform") = GlobalTransform::from_xyz(10.0, 3.0, 0.0);
+        app.update();
+
+        let path_type = app
+            .world
+            .get::<PathType>(tracked)
+            .expect("tracked entity has a PathType");
+        assert_eq!(
+            path_type.current_path.nodes,
+            vec![Vec2::new(5.0, 0.0), Vec2::new(-5.0, -3.0)]
+        );
+    }
+
+    #[test]
+    fn test_grid_snap() {
+        let mut app = App::new();
+        app.add_systems(Update, update_entity_position);
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::new(8.0, 2.0), 'A'),
+            PuncturePoint::new(Vec2::new(8.0, 8.0), 'B'),
+        ];
+        let tracked = app
+            .world
+            .spawn((
+                Transform::default(),
+                PathType::new(Vec2::ZERO, puncture_points),
+                GridSnap {
+                    cell: Vec2::splat(10.0),
+                },
+            ))
+            .id();
+
+        for (x, y) in [
+            (0.1, -0.2),
+            (9.8, 0.3),
+            (10.2, -0.4),
+            (10.2, 9.9),
+            (9.7, 10.3),
+            (0.3, 10.1),
+        ] {
+            *app.world
+                .get_mut::<Transform>(tracked)
+                .expect("tracked entity has a Transform") = Transform::from_xyz(x, y, 0.0);
+            app.update();
+        }
+
+        let path_type = app
+            .world
+            .get::<PathType>(tracked)
+            .expect("tracked entity has a PathType");
+        assert_eq!(
+            path_type.current_path.nodes,
+            vec![
+                Vec2::ZERO,
+                Vec2::new(10.0, 0.0),
+                Vec2::new(10.0, 10.0),
+                Vec2::new(0.0, 10.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_debug_lines_use_configured_z() {
+        let path = PLPath::new(vec![Vec2::ZERO, Vec2::new(4.0, 0.0), Vec2::new(4.0, 4.0)]);
+        let config = PathDebugConfig {
+            z: 5.0,
+            ..Default::default()
+        };
+        let lines = debug_lines(&path, &config);
+        assert_eq!(lines.len(), 3);
+        assert!(lines
+            .iter()
+            .all(|(start, end)| start.z == 5.0 && end.z == 5.0));
+        assert_eq!(lines[0].1.truncate(), Vec2::new(4.0, 0.0));
+
+        let dashed = PathDebugConfig {
+            dash_length: Some(1.0),
+            ..config
+        };
+        assert_eq!(debug_lines(&path, &dashed).len(), 2 + 2 + 3);
+    }
+
+    #[test]
+    fn test_word_label_follows_path() {
+        let mut app = App::new();
+        app.insert_resource(PathDebugConfig {
+            show_word_label: true,
+            ..Default::default()
+        })
+        .add_systems(Update, update_word_labels);
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'A')];
+        let path = PLPath::new(vec![
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+        ]);
+        let tracked = app
+            .world
+            .spawn(PathType::from_path(path, puncture_points.into()))
+            .id();
+        app.update();
+        app.world
+            .get_mut::<PathType>(tracked)
+            .expect("tracked entity has a PathType")
+            .push(&Vec2::new(-1.0, -1.0));
+        app.update();
+
+        let mut labels = app.world.query::<(&PathWordLabel, &Text, &Transform)>();
+        let (label, text, transform) = labels
+            .get_single(&app.world)
+            .expect("exactly one word label");
+        assert_eq!(label.target, tracked);
+        assert_eq!(text.sections[0].value, "a");
+        assert_eq!(
+            transform.translation.truncate(),
+            Vec2::new(-1.0, -1.0) + WORD_LABEL_OFFSET
+        );
+    }
+
+    #[test]
+    fn test_path_tracking_can_be_paused() {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins(PathPlugin);
+        let tracked = app
+            .world
+            .spawn((
+                Transform::default(),
+                PathType::<char>::new(Vec2::ZERO, vec![]),
+            ))
+            .id();
+        let move_to = |app: &mut App, x: f32| {
+            *app.world
+                .get_mut::<Transform>(tracked)
+                .expect("tracked entity has a Transform") = Transform::from_xyz(x, 0.0, 0.0);
+            app.update();
+        };
+        let nodes = |app: &App| {
+            app.world
+                .get::<PathType>(tracked)
+                .expect("tracked entity has a PathType")
+                .current_path
+                .nodes
+                .clone()
+        };
+
+        move_to(&mut app, 1.0);
+        assert_eq!(nodes(&app), vec![Vec2::ZERO, Vec2::X]);
+
+        app.insert_resource(PathTrackingEnabled(false));
+        move_to(&mut app, 2.0);
+        move_to(&mut app, 3.0);
+        assert_eq!(nodes(&app), vec![Vec2::ZERO, Vec2::X]);
+
+        app.insert_resource(PathTrackingEnabled(true));
+        move_to(&mut app, 4.0);
+        assert_eq!(nodes(&app), vec![Vec2::ZERO, Vec2::new(4.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_recommended_sample_interval() {
+        let interval = PathTimer::recommended_sample_interval;
+        assert_eq!(interval(10.0, 100.0).as_millis(), 100);
+        assert_eq!(interval(50.0, 200.0).as_millis(), 250);
+        assert_eq!(interval(1.0, 1000.0), PathTimer::MIN_SAMPLE_INTERVAL);
+        assert_eq!(interval(100.0, 1.0), PathTimer::MAX_SAMPLE_INTERVAL);
+        assert_eq!(interval(10.0, 0.0), PathTimer::MAX_SAMPLE_INTERVAL);
+    }
+
+    #[test]
+    fn test_debug_plugin_inserts_config() {
+        let config = PathDebugConfig::builder()
+            .line_width(5.0)
+            .z(3.0)
+            .show_word_label(true)
+            .build();
+        let mut app = App::new();
+        app.add_plugins(PathDebugPlugin::with_config(config.clone()));
+        assert_eq!(app.world.resource::<PathDebugConfig>(), &config);
+    }
+
+    #[test]
+    fn test_branch_cut_lines() {
+        let puncture_points = PuncturePoint::auto_named([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(5.0, -5.0),
+            Vec2::new(50.0, 0.0),
+        ]);
+        let viewport = Rect::new(-10.0, -10.0, 10.0, 10.0);
+        assert_eq!(
+            branch_cut_lines(&puncture_points, viewport),
+            vec![
+                (Vec2::new(0.0, 0.0), Vec2::new(0.0, 10.0)),
+                (Vec2::new(5.0, -5.0), Vec2::new(5.0, 10.0)),
+            ]
+        );
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_scene_round_trip() {
+        use bevy::{ecs::entity::EntityHashMap, scene::serde::SceneDeserializer};
+        use serde::de::DeserializeSeed;
+
+        let mut app = App::new();
+        app.add_plugins(PathPlugin);
+        let registry = app.world.resource::<AppTypeRegistry>().clone();
+
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO, Vec2::new(4.0, 0.0)]);
+        let mut path_type = PathType::new(Vec2::new(-1.0, 1.0), puncture_points);
+        path_type.push(&Vec2::new(1.0, 1.0));
+        path_type.push(&Vec2::new(1.0, -1.0));
+        let word = path_type.word();
+        assert_eq!(word, "a");
+        app.world.spawn(path_type);
+
+        let scene = DynamicScene::from_world(&app.world);
+        let ron = scene.serialize_ron(&registry).expect("scene serializes");
+        assert!(!ron.contains("word:"));
+
+        let scene = {
+            let deserializer = SceneDeserializer {
+                type_registry: &registry.read(),
+            };
+            let mut ron_deserializer = ron::de::Deserializer::from_str(&ron).expect("valid ron");
+            deserializer
+                .deserialize(&mut ron_deserializer)
+                .expect("scene deserializes")
+        };
+
+        let mut world = World::new();
+        world.insert_resource(registry);
+        scene
+            .write_to_world(&mut world, &mut EntityHashMap::default())
+            .expect("scene loads");
+        let loaded = world
+            .query::<&PathType>()
+            .get_single(&world)
+            .expect("one path entity");
+        assert_eq!(loaded.word(), word);
+        assert!(loaded.validate());
+    }
+
+    #[test]
+    fn test_tracing_pen_up() {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins(PathPlugin);
+        let tracked = app
+            .world
+            .spawn((
+                Transform::default(),
+                PathType::<char>::new(Vec2::ZERO, vec![]),
+                Tracing(true),
+            ))
+            .id();
+        let move_to = |app: &mut App, x: f32, y: f32| {
+            *app.world
+                .get_mut::<Transform>(tracked)
+                .expect("tracked entity has a Transform") = Transform::from_xyz(x, y, 0.0);
+            app.update();
+        };
+        let end = |app: &App| {
+            app.world
+                .get::<PathType>(tracked)
+                .expect("tracked entity has a PathType")
+                .current_path
+                .nodes
+                .last()
+                .copied()
+        };
+
+        move_to(&mut app, 1.0, 0.0);
+        assert_eq!(end(&app), Some(Vec2::X));
+
+        *app.world
+            .get_mut::<Tracing>(tracked)
+            .expect("tracked entity has Tracing") = Tracing(false);
+        move_to(&mut app, 2.0, 1.0);
+        move_to(&mut app, 3.0, -1.0);
+        assert_eq!(end(&app), Some(Vec2::X));
+
+        *app.world
+            .get_mut::<Tracing>(tracked)
+            .expect("tracked entity has Tracing") = Tracing(true);
+        move_to(&mut app, 4.0, 0.0);
+        assert_eq!(end(&app), Some(Vec2::new(4.0, 0.0)));
+    }
+
+    #[test]
+    fn test_word_timer_throttles_words() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_plugins(PathPlugin)
+            .insert_resource(WordTimer::new(Duration::from_millis(200)));
+        let puncture_points = PuncturePoint::auto_named([Vec2::ZERO]);
+        let tracked = app
+            .world
+            .spawn((
+                Transform::from_xyz(-1.0, 1.0, 0.0),
+                PathType::new(Vec2::new(-1.0, 1.0), puncture_points).with_openness(Openness::Open),
+            ))
+            .id();
+        let move_to = |app: &mut App, x: f32, y: f32| {
+            app.world
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_millis(100));
+            *app.world
+                .get_mut::<Transform>(tracked)
+                .expect("tracked entity has a Transform") = Transform::from_xyz(x, y, 0.0);
+            app.update();
+            let path_type = app
+                .world
+                .get::<PathType>(tracked)
+                .expect("tracked entity has a PathType");
+            (path_type.current_path.nodes.len(), path_type.word())
+        };
+
+        assert_eq!(move_to(&mut app, 1.0, 1.0), (2, String::new()));
+        assert_eq!(move_to(&mut app, 1.0, -1.0), (3, "a".to_string()));
+        assert_eq!(move_to(&mut app, 1.0, -3.0), (3, "a".to_string()));
+
+        app.world.remove_resource::<WordTimer>();
+        move_to(&mut app, 3.0, 1.0);
+        let path_type = app
+            .world
+            .get::<PathType>(tracked)
+            .expect("tracked entity has a PathType");
+        assert!(!path_type.is_word_stale());
+    }
+
+    #[test]
+    fn test_shared_punctures_are_synced() {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins(PathPlugin);
+        let handle = PuncturePointsHandle::new(PuncturePoint::auto_named([Vec2::new(5.0, 0.0)]));
+        let path = PLPath::new([
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ]);
+        let entities: Vec<Entity> = (0..2)
+            .map(|_| {
+                let mut path_type = PathType::from_handle(Vec2::new(-1.0, 1.0), &handle);
+                path_type.current_path = path.clone();
+                path_type.update_word();
+                app.world
+                    .spawn((Transform::from_xyz(-1.0, -1.0, 0.0), path_type))
+                    .id()
+            })
+            .collect();
+        app.update();
+        let words = |app: &App| -> Vec<String> {
+            entities
+                .iter()
+                .map(|entity| {
+                    app.world
+                        .get::<PathType>(*entity)
+                        .expect("entity has a PathType")
+                        .word()
+                })
+                .collect()
+        };
+        assert_eq!(words(&app), vec!["", ""]);
+
+        handle.set(PuncturePoint::auto_named([Vec2::ZERO]));
+        app.update();
+        assert_eq!(words(&app), vec!["a", "a"]);
+    }
+
+    #[test]
+    fn test_segment_labels_match_segment_generators() {
+        let mut app = App::new();
+        app.insert_resource(PathDebugConfig::builder().show_segment_labels(true).build())
+            .add_systems(Update, update_segment_labels);
+        let puncture_points = vec![
+            PuncturePoint::new(Vec2::ZERO, 'A'),
+            PuncturePoint::new(Vec2::new(2.0, 0.0), 'B'),
+        ];
+        let path = PLPath::new(vec![
+            Vec2::new(-1.0, 1.0),
+            Vec2::new(3.0, 1.0),
+            Vec2::new(3.0, -1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(1.0, 1.0),
+        ]);
+        let path_type = PathType::from_path(path, puncture_points.into());
+        let tracked = app.world.spawn(path_type.clone()).id();
+        app.update();
+
+        let mut labels = app.world.query::<(&PathSegmentLabel, &Text, &Transform)>();
+        let mut found: Vec<(usize, String, Vec2)> = labels
+            .iter(&app.world)
+            .map(|(label, text, transform)| {
+                assert_eq!(label.target, tracked);
+                (
+                    label.index,
+                    text.sections[0].value.clone(),
+                    transform.translation.truncate(),
+                )
+            })
+            .collect();
+        found.sort_by_key(|(index, _, _)| *index);
+        let expected: Vec<(usize, String, Vec2)> = path_type
+            .segment_generators()
+            .iter()
+            .zip(path_type.current_path.segments(Openness::Loop))
+            .enumerate()
+            .filter(|(_, (letters, _))| !letters.is_empty())
+            .map(|(index, (letters, (start, end)))| {
+                (index, path_type.render(letters), (*start + *end) / 2.0)
+            })
+            .collect();
+        assert_eq!(found, expected);
+        assert_eq!(
+            found
+                .iter()
+                .map(|(_, fragment, _)| fragment.as_str())
+                .collect::<Vec<_>>(),
+            ["ab", "A"]
+        );
+
+        app.insert_resource(PathDebugConfig::default());
+        app.update();
+        assert_eq!(labels.iter(&app.world).count(), 0);
+    }
+
+    #[test]
+    fn test_segment_labels_wait_for_stale_words() {
+        let mut app = App::new();
+        app.insert_resource(PathDebugConfig::builder().show_segment_labels(true).build())
+            .add_systems(Update, update_segment_labels);
+        let mut path_type = PathType::new(
+            Vec2::new(-1.0, 1.0),
+            vec![PuncturePoint::new(Vec2::ZERO, 'A')],
+        )
+        .with_openness(Openness::Open);
+        path_type.push(&Vec2::new(1.0, 1.0));
+        let tracked = app.world.spawn(path_type).id();
+        let mut labels = app.world.query::<(&PathSegmentLabel, &Text, &Transform)>();
+        let mut found = |app: &App| -> Vec<(usize, String, Vec2)> {
+            let mut found: Vec<(usize, String, Vec2)> = labels
+                .iter(&app.world)
+                .map(|(label, text, transform)| {
+                    (
+                        label.index,
+                        text.sections[0].value.clone(),
+                        transform.translation.truncate(),
+                    )
+                })
+                .collect();
+            found.sort_by_key(|(index, _, _)| *index);
+            found
+        };
+        app.update();
+        let before = found(&app);
+        assert_eq!(before, [(0, "a".to_string(), Vec2::new(0.0, 1.0))]);
+
+        // Pushed without recomputing the word, as under a `WordTimer`.
+        let mut path_type = app
+            .world
+            .get_mut::<PathType>(tracked)
+            .expect("tracked entity has a PathType");
+        path_type.defer_word = true;
+        for node in [
+            Vec2::new(3.0, 1.0),
+            Vec2::new(3.0, -1.0),
+            Vec2::new(-3.0, -1.0),
+            Vec2::new(-3.0, 2.0),
+            Vec2::new(3.0, 2.0),
+        ] {
+            path_type.push(&node);
+        }
+        assert!(path_type.is_word_stale());
+        app.update();
+        assert_eq!(found(&app), before);
+
+        app.world
+            .get_mut::<PathType>(tracked)
+            .expect("tracked entity has a PathType")
+            .update_word();
+        app.update();
+        let path_type = app
+            .world
+            .get::<PathType>(tracked)
+            .expect("tracked entity has a PathType");
+        let expected: Vec<(usize, String, Vec2)> = path_type
+            .segment_generators()
+            .iter()
+            .zip(path_type.current_path.segments(Openness::Open))
+            .enumerate()
+            .filter(|(_, (letters, _))| !letters.is_empty())
+            .map(|(index, (letters, (start, end)))| {
+                (index, path_type.render(letters), (*start + *end) / 2.0)
+            })
+            .collect();
+        assert_eq!(found(&app), expected);
+        assert_ne!(expected, before);
+    }
+
+    #[test]
+    fn test_standing_still_leaves_path_unchanged() {
+        #[derive(Resource, Default)]
+        struct PathChanges(usize);
+
+        fn count_path_changes(
+            changed: Query<(), Changed<PathType>>,
+            mut changes: ResMut<PathChanges>,
+        ) {
+            changes.0 += changed.iter().count();
+        }
+
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<PathChanges>()
+            .add_plugins(PathPlugin)
+            .insert_resource(WordTimer::default())
+            .add_systems(Update, count_path_changes.after(update_entity_position));
+        let tracked = app
+            .world
+            .spawn((
+                Transform::from_xyz(-1.0, 1.0, 0.0),
+                PathType::new(
+                    Vec2::new(-1.0, 1.0),
+                    PuncturePoint::auto_named([Vec2::ZERO]),
+                ),
+            ))
+            .id();
+        let changes = |app: &App| app.world.resource::<PathChanges>().0;
+
+        // Spawning counts as a change.
+        app.update();
+        assert_eq!(changes(&app), 1);
+        app.update();
+        app.update();
+        assert_eq!(changes(&app), 1);
+
+        *app.world
+            .get_mut::<Transform>(tracked)
+            .expect("tracked entity has a Transform") = Transform::from_xyz(1.0, 1.0, 0.0);
+        app.update();
+        assert_eq!(changes(&app), 2);
+        app.update();
+        assert_eq!(changes(&app), 2);
+    }
+
+    #[test]
+    fn test_auto_close_loop() {
+        let mut app = App::new();
+        app.init_resource::<Time>().add_plugins(PathPlugin);
+        let puncture_points = vec![PuncturePoint::new(Vec2::ZERO, 'A')];
+        let start = Vec2::new(-1.0, 1.0);
+        let tracked = app
+            .world
+            .spawn((
+                Transform::from_translation(start.extend(0.0)),
+                PathType::new(start, puncture_points),
+                AutoCloseLoop { threshold: 0.2 },
+            ))
+            .id();
+        let mut reader = app.world.resource_mut::<Events<LoopClosed>>().get_reader();
+        let mut move_to = |app: &mut App, position: Vec2| {
+            *app.world
+                .get_mut::<Transform>(tracked)
+                .expect("tracked entity has a Transform") =
+                Transform::from_translation(position.extend(0.0));
+            app.update();
+            reader
+                .read(app.world.resource::<Events<LoopClosed>>())
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        for position in [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, -1.0),
+            Vec2::new(-1.0, -1.0),
+        ] {
+            assert_eq!(move_to(&mut app, position), []);
+        }
+        assert_eq!(
+            move_to(&mut app, Vec2::new(-1.0, 0.9)),
+            [LoopClosed {
+                entity: tracked,
+                word: "a".to_string(),
+            }]
+        );
+        let path_type = app
+            .world
+            .get::<PathType>(tracked)
+            .expect("tracked entity has a PathType");
+        assert_eq!(path_type.current_path.nodes, vec![start]);
+
+        // Lingering near the start does not close the new loop straight away.
+        assert_eq!(move_to(&mut app, Vec2::new(-1.0, 0.95)), []);
+        assert_eq!(move_to(&mut app, Vec2::new(-1.0, 1.05)), []);
+    }
+}
@@ -0,0 +1,326 @@
+use std::{fmt::Display, hash::Hash};
+
+/// A type that can name puncture points, and so be used as the letters of a word.
+///
+/// `char` names use the classic encoding: the lowercase letter is the clockwise generator and
+/// the uppercase letter is its inverse, so words read like `abA`. Any other label is written with
+/// its [`Display`] implementation, space separated, with inverses marked `^-1`, e.g. `Red Blue^-1`,
+/// so `impl Label for MyLabel {}` is enough for a type that is already `Clone + Eq + Hash + Display`.
+pub trait Label: Clone + Eq + Hash + Display {
+    /// Appends the letter for winding `exponent` times (`1` or `-1`) around a puncture point named `self` to `word`.
+    fn write_letter(&self, exponent: i32, word: &mut String) {
+        if !word.is_empty() {
+            word.push(' ');
+        }
+        word.push_str(&self.to_string());
+        if exponent < 0 {
+            word.push_str("^-1");
+        }
+    }
+}
+
+impl Label for char {
+    fn write_letter(&self, exponent: i32, word: &mut String) {
+        word.push(letter_with_case(*self, exponent < 0));
+    }
+}
+
+/// Writes a word given as `(label, exponent)` letters, using [`Label::write_letter`].
+pub(crate) fn render_letters<L: Label>(letters: &[(L, i32)]) -> String {
+    let mut word = String::new();
+    for (label, exponent) in letters {
+        label.write_letter(*exponent, &mut word);
+    }
+    word
+}
+
+/// Checks if two letters are inverse generators of each other, like `a` and `A`, see [`invert_letter`].
+fn are_inverses(a: char, b: char) -> bool {
+    a != b && invert_letter(a) == b
+}
+
+/// Flips the case of a letter, like `a` to `A` and `É` to `é`.
+///
+/// Letters whose case change is not a single character that changes back, like `ß`, are kept as they are,
+/// so flipping twice always gives back the original letter.
+fn invert_letter(c: char) -> char {
+    // The only character of `chars`, if there is exactly one.
+    fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+        chars.next().filter(|_| chars.next().is_none())
+    }
+    let (flipped, back) = if c.is_lowercase() {
+        let flipped = single(c.to_uppercase());
+        (
+            flipped,
+            flipped.and_then(|flipped| single(flipped.to_lowercase())),
+        )
+    } else {
+        let flipped = single(c.to_lowercase());
+        (
+            flipped,
+            flipped.and_then(|flipped| single(flipped.to_uppercase())),
+        )
+    };
+    flipped.filter(|_| back == Some(c)).unwrap_or(c)
+}
+
+/// Writes `c` in upper case if `upper` is set, and in lower case otherwise, flipping its case like [`invert_word`].
+///
+/// Letters whose case does not flip back, like `ß`, are kept as they are.
+pub(crate) fn letter_with_case(c: char, upper: bool) -> char {
+    if c.is_lowercase() == upper {
+        invert_letter(c)
+    } else {
+        c
+    }
+}
+
+/// Freely reduces a word in place by cancelling adjacent inverse pairs such as `aA` or `Bb`.
+///
+/// The word is read by characters, like [`is_reduced`], so the result is always reduced.
+///
+/// # Examples
+///
+/// ```
+/// use charred_path::word::simplify_word;
+///
+/// let mut word = "abBAc".to_string();
+/// simplify_word(&mut word);
+/// assert_eq!(word, "c");
+/// ```
+pub fn simplify_word(word: &mut String) {
+    let mut reduced = String::with_capacity(word.len());
+    for c in word.chars() {
+        if reduced
+            .chars()
+            .next_back()
+            .is_some_and(|last| are_inverses(last, c))
+        {
+            reduced.pop();
+        } else {
+            reduced.push(c);
+        }
+    }
+    *word = reduced;
+}
+
+/// Checks that a word is freely reduced, i.e. that no letter is next to its inverse, like `aA` or `Bb`.
+///
+/// The word is read by characters, so multibyte characters are compared whole.
+///
+/// # Examples
+///
+/// ```
+/// use charred_path::word::is_reduced;
+///
+/// assert!(is_reduced("abAB"));
+/// assert!(!is_reduced("abBA"));
+/// ```
+pub fn is_reduced(word: &str) -> bool {
+    !word
+        .chars()
+        .zip(word.chars().skip(1))
+        .any(|(a, b)| are_inverses(a, b))
+}
+
+/// Checks that a word of `(label, exponent)` letters is freely reduced, the counterpart of [`is_reduced`].
+pub(crate) fn letters_are_reduced<L: PartialEq>(letters: &[(L, i32)]) -> bool {
+    !letters
+        .windows(2)
+        .any(|pair| pair[0].0 == pair[1].0 && pair[0].1 == -pair[1].1)
+}
+
+/// Reduces a word of `(label, exponent)` letters in place, using both free reduction and the
+/// relation `x^n = 1` for every generator `x` with a finite order `n` given by `order`.
+///
+/// Runs of a finite-order letter are shortened to their length modulo the order, and the word
+/// is freely reduced again until nothing changes. An order of `0` is ignored.
+pub(crate) fn reduce_letters<L: PartialEq>(
+    letters: &mut Vec<(L, i32)>,
+    order: impl Fn(&L) -> Option<u32>,
+) {
+    loop {
+        let mut reduced: Vec<(L, i32)> = Vec::with_capacity(letters.len());
+        for (label, exponent) in letters.drain(..) {
+            match reduced.last() {
+                Some((last, last_exponent)) if *last == label && *last_exponent == -exponent => {
+                    reduced.pop();
+                }
+                _ => reduced.push((label, exponent)),
+            }
+        }
+        let mut changed = false;
+        let mut start = 0;
+        while start < reduced.len() {
+            let run = reduced[start..]
+                .iter()
+                .take_while(|letter| **letter == reduced[start])
+                .count();
+            let kept = order(&reduced[start].0)
+                .and_then(|order| run.checked_rem(order as usize))
+                .unwrap_or(run);
+            if kept < run {
+                reduced.drain(start + kept..start + run);
+                changed = true;
+            }
+            start += kept;
+        }
+        *letters = reduced;
+        if !changed {
+            return;
+        }
+    }
+}
+
+/// Returns the inverse of a word: the characters in reverse order, each with its case flipped.
+///
+/// Case is flipped over all of Unicode, and characters without a case that flips back, like `ß`,
+/// are kept as they are, so inverting twice gives back the word.
+///
+/// # Examples
+///
+/// ```
+/// use charred_path::word::invert_word;
+///
+/// assert_eq!(invert_word("abAB"), "baBA");
+/// ```
+pub fn invert_word(word: &str) -> String {
+    word.chars().rev().map(invert_letter).collect()
+}
+
+/// Concatenates two freely reduced words and reduces the result.
+///
+/// Since both words are already reduced, cancellation can only happen at the join,
+/// so only the end of `a` and the start of `b` are inspected.
+///
+/// # Examples
+///
+/// ```
+/// use charred_path::word::reduce_concat;
+///
+/// assert_eq!(reduce_concat("abc", "Cd"), "abd");
+/// ```
+pub fn reduce_concat(a: &str, b: &str) -> String {
+    let (mut head, mut tail) = (a, b);
+    while let (Some(x), Some(y)) = (head.chars().next_back(), tail.chars().next()) {
+        if !are_inverses(x, y) {
+            break;
+        }
+        head = &head[..head.len() - x.len_utf8()];
+        tail = &tail[y.len_utf8()..];
+    }
+    [head, tail].concat()
+}
+
+/// Returns every cyclic rotation of a word, starting with the word itself.
+///
+/// Rotations are taken over characters, so a word of `n` characters has `n` rotations.
+/// The empty word has itself as its only rotation.
+pub fn cyclic_rotations(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    if chars.is_empty() {
+        return vec![String::new()];
+    }
+    (0..chars.len())
+        .map(|i| chars[i..].iter().chain(&chars[..i]).collect())
+        .collect()
+}
+
+/// Returns the lexicographically least cyclic rotation of a word.
+///
+/// Words which are rotations of each other share the same least rotation, so this is a
+/// canonical representative of the rotation class.
+pub fn min_rotation(word: &str) -> String {
+    cyclic_rotations(word).into_iter().min().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simplify_word_with_multibyte_chars() {
+        let mut word = "ßAa".to_string();
+        simplify_word(&mut word);
+        assert_eq!(word, "ß");
+
+        for (word, expected) in [("éÉ", ""), ("aéΩωÉb", "ab"), ("ÉbBé", ""), ("éE", "éE")]
+        {
+            let mut simplified = word.to_string();
+            simplify_word(&mut simplified);
+            assert_eq!(simplified, expected);
+            assert!(is_reduced(&simplified));
+        }
+    }
+
+    #[test]
+    fn test_invert_word() {
+        assert_eq!(invert_word("abAB"), "baBA");
+        assert_eq!(invert_word(&invert_word("abAB")), "abAB");
+        assert_eq!(invert_word("aßC"), "cßA");
+        assert_eq!(invert_word("éΩ"), "ωÉ");
+        assert_eq!(invert_word(""), "");
+        for word in ["éΩßǅİ1", "\u{212A}kK"] {
+            assert_eq!(invert_word(&invert_word(word)), word);
+        }
+    }
+
+    #[test]
+    fn test_cyclic_rotations() {
+        assert_eq!(cyclic_rotations("abc"), vec!["abc", "bca", "cab"]);
+        assert_eq!(cyclic_rotations(""), vec![""]);
+        assert_eq!(min_rotation("cab"), "abc");
+        assert_eq!(min_rotation("cab"), min_rotation("bca"));
+    }
+
+    #[test]
+    fn test_reduce_concat() {
+        assert_eq!(reduce_concat("ab", "BA"), "");
+        assert_eq!(reduce_concat("ab", "Bc"), "ac");
+        assert_eq!(reduce_concat("", "aB"), "aB");
+        assert_eq!(reduce_concat("ab", "ab"), "abab");
+    }
+
+    #[test]
+    fn test_reduce_letters_with_orders() {
+        let letters = |word: &str| -> Vec<(char, i32)> {
+            word.chars()
+                .map(|c| {
+                    (
+                        c.to_ascii_uppercase(),
+                        if c.is_ascii_lowercase() { 1 } else { -1 },
+                    )
+                })
+                .collect()
+        };
+        let order = |label: &char| (*label == 'A').then_some(2);
+        for (word, expected) in [
+            ("aa", ""),
+            ("AA", ""),
+            ("aaa", "a"),
+            ("baab", "bb"),
+            ("bb", "bb"),
+            ("bAab", "bb"),
+        ] {
+            let mut reduced = letters(word);
+            reduce_letters(&mut reduced, order);
+            assert_eq!(render_letters(&reduced), expected);
+        }
+    }
+
+    #[test]
+    fn test_is_reduced() {
+        assert!(is_reduced(""));
+        assert!(is_reduced("abAB"));
+        assert!(is_reduced("aa"));
+        assert!(!is_reduced("abBA"));
+        assert!(!is_reduced("cAa"));
+        assert!(!is_reduced("éÉ"));
+        assert!(is_reduced("éE"));
+        assert!(is_reduced("ßß"));
+        assert!(!is_reduced("éaA"));
+
+        assert!(letters_are_reduced(&[('A', 1), ('B', 1), ('A', -1)]));
+        assert!(!letters_are_reduced(&[('A', 1), ('B', 1), ('B', -1)]));
+    }
+}